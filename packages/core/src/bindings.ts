@@ -0,0 +1,183 @@
+import { Component, createComponentVNode, createTextVNode } from "inferno";
+import { VNodeFlags } from "inferno-vnode-flags";
+import { Reaction } from "mobx";
+
+// The SWC plugin's `bindings` option compiles the reactive text children and
+// attributes of render closures into bindings. A binding observes its own
+// expression and writes to its DOM node when it changes, so the component
+// rendering it does not re-render or diff.
+
+type Primitive = string | number | boolean | null | undefined;
+
+function isPrimitive(value: unknown): value is Primitive {
+  return value === null || (typeof value !== "object" && typeof value !== "function");
+}
+
+function toText(value: Primitive) {
+  return value == null || typeof value === "boolean" ? "" : String(value);
+}
+
+class TextBinding extends Component<{ read: () => unknown }> {
+  private reaction = new Reaction("TextBinding", () => this.update());
+  private value: unknown;
+  private isText = false;
+
+  constructor(props: { read: () => unknown }) {
+    super(props);
+    this.track(props.read);
+  }
+  private track(read: () => unknown) {
+    this.reaction.track(() => {
+      this.value = read();
+    });
+  }
+  // Writes text directly, anything else, like a VNode, renders the binding again
+  private write() {
+    const dom = (this as any).$LI?.dom as Text | undefined;
+
+    if (this.isText && isPrimitive(this.value) && dom) {
+      dom.nodeValue = toText(this.value);
+      return true;
+    }
+
+    return false;
+  }
+  private update() {
+    this.track(this.props.read);
+
+    if (!this.write()) {
+      this.forceUpdate();
+    }
+  }
+  shouldComponentUpdate(nextProps: { read: () => unknown }) {
+    this.track(nextProps.read);
+
+    return !this.write();
+  }
+  componentWillUnmount() {
+    this.reaction.dispose();
+  }
+  render() {
+    this.isText = isPrimitive(this.value);
+
+    return this.isText
+      ? createTextVNode(toText(this.value as Primitive))
+      : (this.value as any);
+  }
+}
+
+/**
+ * A text child updating its text node directly
+ */
+export function bindText(read: () => unknown) {
+  return createComponentVNode(
+    VNodeFlags.ComponentClass,
+    TextBinding,
+    { read },
+    null,
+    null
+  );
+}
+
+// Properties reflecting the current state of the element, not the initial one
+const PROPERTIES = new Set(["value", "checked", "selected", "muted", "indeterminate"]);
+
+function setAttribute(element: Element, name: string, value: unknown) {
+  if (name === "className") {
+    element.className = value == null ? "" : String(value);
+  } else if (PROPERTIES.has(name)) {
+    (element as any)[name] = value;
+  } else if (value == null || value === false) {
+    element.removeAttribute(name === "htmlFor" ? "for" : name);
+  } else {
+    element.setAttribute(
+      name === "htmlFor" ? "for" : name,
+      value === true ? "" : String(value)
+    );
+  }
+}
+
+type Attrs = Record<string, () => unknown>;
+
+interface AttrBinding {
+  attrs: Attrs;
+  reactions: Reaction[];
+  updates: Array<() => void>;
+  isDetached: boolean;
+}
+
+// Render closures create a new ref on every render, so the reactions are kept
+// per element and read the attributes of the latest render
+const attrBindings = new WeakMap<Element, AttrBinding>();
+
+function bindElement(element: Element, attrs: Attrs) {
+  const existing = attrBindings.get(element);
+
+  if (existing) {
+    existing.attrs = attrs;
+    existing.isDetached = false;
+    existing.updates.forEach((update) => update());
+    return;
+  }
+
+  const binding: AttrBinding = {
+    attrs,
+    reactions: [],
+    updates: [],
+    isDetached: false,
+  };
+
+  for (const name in attrs) {
+    const reaction = new Reaction("AttributeBinding", () => update());
+    const update = () =>
+      reaction.track(() => setAttribute(element, name, binding.attrs[name]()));
+
+    update();
+    binding.reactions.push(reaction);
+    binding.updates.push(update);
+  }
+
+  attrBindings.set(element, binding);
+}
+
+// A changed ref is detached before the new one is attached to the same
+// element, so the reactions are only disposed when no ref attaches again
+function unbindElement(element: Element) {
+  const binding = attrBindings.get(element);
+
+  if (!binding) {
+    return;
+  }
+
+  binding.isDetached = true;
+  queueMicrotask(() => {
+    if (binding.isDetached && attrBindings.get(element) === binding) {
+      binding.reactions.forEach((reaction) => reaction.dispose());
+      attrBindings.delete(element);
+    }
+  });
+}
+
+/**
+ * A ref setting attributes of the element directly, forwarding the element to
+ * the ref of the element, if any
+ */
+export function bindAttrs(attrs: Attrs, ref?: any) {
+  let bound: Element | null = null;
+
+  return (element: Element | null) => {
+    if (element) {
+      bindElement(element, attrs);
+    } else if (bound) {
+      unbindElement(bound);
+    }
+
+    bound = element;
+
+    if (typeof ref === "function") {
+      ref(element);
+    } else if (ref) {
+      ref.current = element;
+    }
+  };
+}
@@ -10,6 +10,20 @@ export function getCurrentComponent() {
   return currentComponent;
 }
 
+export function withCurrentComponent<T>(
+  component: RaskComponent<any>,
+  fn: () => T
+): T {
+  const previousComponent = currentComponent;
+  currentComponent = component;
+
+  try {
+    return fn();
+  } finally {
+    currentComponent = previousComponent;
+  }
+}
+
 export function useMountEffect(cb: () => void) {
   if (!currentComponent) {
     throw new Error("Only use useMountEffect in component setup");
@@ -77,6 +91,8 @@ export class RaskComponent<P extends Props<any>> extends Component<P> {
   private reaction: Reaction = this.createReaction();
   // Flag to prevent props from tracking in render scope (We use props reconciliation)
   isRendering = false;
+  // Rendered to a string on the server, where effects never run
+  isServer = false;
   effects: Array<{ isDirty: boolean; run: () => void }> = [];
   contexts = new Map();
   getContext(context: unknown) {
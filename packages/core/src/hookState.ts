@@ -0,0 +1,93 @@
+import { toJS } from "mobx";
+import { getCurrentComponent, useCleanup } from "./component";
+
+// The SWC plugin's `hookIds` option passes a call-site ID, built from the file,
+// component and ordinal of the hook, as the last argument of useState,
+// useAsync, useAction and useDerived. States created with an ID can be
+// snapshotted and restored, on the client after server rendering or after a
+// reload. Several instances of a component share call-site IDs, so snapshot
+// keys also count the instances in creation order: `<id>#<instance>`.
+
+const liveStates = new Map<object, string>();
+let restoredStates = new Map<string, unknown>();
+const restoredCounts = new Map<string, number>();
+
+/**
+ * The restored value of the next state created at the call-site
+ */
+export function takeRestoredState(id: string): unknown {
+  const instance = restoredCounts.get(id) ?? 0;
+  restoredCounts.set(id, instance + 1);
+
+  const key = `${id}#${instance}`;
+  const value = restoredStates.get(key);
+  restoredStates.delete(key);
+
+  return value;
+}
+
+/**
+ * Includes a state in snapshots for as long as its component is mounted
+ */
+export function trackState(id: string, state: object) {
+  liveStates.set(state, id);
+
+  const component = getCurrentComponent();
+
+  // Server rendered components are cleaned up before the snapshot is taken
+  if (component && !component.isServer) {
+    useCleanup(() => liveStates.delete(state));
+  }
+}
+
+/**
+ * Serializable values of the tracked states, keyed by call-site and instance
+ */
+export function snapshotState(): Record<string, unknown> {
+  const instances = new Map<string, number>();
+  const snapshot: Record<string, unknown> = {};
+
+  liveStates.forEach((id, state) => {
+    const instance = instances.get(id) ?? 0;
+    instances.set(id, instance + 1);
+    snapshot[`${id}#${instance}`] = toJS(state);
+  });
+
+  return snapshot;
+}
+
+/**
+ * Makes states created from now on start with the values of a snapshot
+ */
+export function restoreState(snapshot: Record<string, unknown>) {
+  restoredStates = new Map(Object.entries(snapshot));
+  restoredCounts.clear();
+}
+
+/**
+ * Forgets the tracked states, called before rendering a page on the server
+ */
+export function resetState() {
+  liveStates.clear();
+  restoredStates.clear();
+  restoredCounts.clear();
+}
+
+/**
+ * Restores states from sessionStorage and saves a snapshot when the page is
+ * hidden, so state survives a reload
+ */
+export function persistState(key = "rask-state") {
+  const saved = sessionStorage.getItem(key);
+
+  if (saved) {
+    sessionStorage.removeItem(key);
+    restoreState(JSON.parse(saved));
+  }
+
+  const save = () => sessionStorage.setItem(key, JSON.stringify(snapshotState()));
+
+  window.addEventListener("pagehide", save);
+
+  return () => window.removeEventListener("pagehide", save);
+}
@@ -0,0 +1,207 @@
+import { hydrate as infernoHydrate } from "inferno-hydrate";
+import { withBatchedEvents } from "./render";
+
+// Markers placed by the SWC plugin's `ssr` target with `hydrate: true`:
+//   <!--[--> ... <!--]-->       a dynamic region, text or content computed at runtime
+//   <!--rk:Key--> ... <!--/rk-->  the rendered output of a component, keyed by
+//                                its file hash and name as in `hash:Name`
+
+/**
+ * The static structure a component renders: elements as `[tag, ...children]`,
+ * static text as strings and dynamic regions as `0`
+ */
+type ExpectedNode = string | 0 | [string, ...ExpectedNode[]];
+
+const structures = new Map<string, ExpectedNode[]>();
+
+/**
+ * Called by the output of the SWC plugin's client transform with `hydrate: true`
+ */
+export function registerHydration(key: string, structure: string) {
+  structures.set(key, JSON.parse(structure));
+}
+
+function isComment(node: Node | undefined, data?: string): node is Comment {
+  return (
+    node?.nodeType === Node.COMMENT_NODE &&
+    (data === undefined || (node as Comment).data === data)
+  );
+}
+
+function isMarker(node: Node) {
+  if (!isComment(node)) {
+    return false;
+  }
+
+  const data = node.data;
+
+  return data === "[" || data === "]" || data === "/rk" || data.startsWith("rk:");
+}
+
+function describe(node: Node | undefined) {
+  if (!node) {
+    return "nothing";
+  }
+
+  if (node.nodeType === Node.TEXT_NODE) {
+    return `text "${node.textContent}"`;
+  }
+
+  if (isComment(node)) {
+    return `<!--${node.data}-->`;
+  }
+
+  return `<${node.nodeName.toLowerCase()}>`;
+}
+
+/**
+ * Index after the marker closing the region or component opened at `index`
+ */
+function skipRegion(nodes: Node[], index: number, open: string, close: string) {
+  let depth = 0;
+
+  for (let i = index; i < nodes.length; i++) {
+    const node = nodes[i];
+
+    if (isComment(node)) {
+      if (node.data === open || (open === "rk:" && node.data.startsWith(open))) {
+        depth++;
+      } else if (node.data === close && --depth === 0) {
+        return i + 1;
+      }
+    }
+  }
+
+  return nodes.length;
+}
+
+/**
+ * Compares DOM nodes against the expected structure, returning a description
+ * of the first mismatch
+ */
+function compare(
+  expected: ExpectedNode[],
+  nodes: Node[],
+  path: string
+): string | undefined {
+  let index = 0;
+
+  for (const node of expected) {
+    const actual = nodes[index];
+
+    if (node === 0) {
+      if (!isComment(actual, "[")) {
+        return `${path}: expected a dynamic region, found ${describe(actual)}`;
+      }
+
+      index = skipRegion(nodes, index, "[", "]");
+      continue;
+    }
+
+    if (typeof node === "string") {
+      if (actual?.nodeType !== Node.TEXT_NODE || actual.textContent !== node) {
+        return `${path}: expected text "${node}", found ${describe(actual)}`;
+      }
+
+      index++;
+      continue;
+    }
+
+    const [tag, ...children] = node;
+
+    if (
+      actual?.nodeType !== Node.ELEMENT_NODE ||
+      actual.nodeName.toLowerCase() !== tag
+    ) {
+      return `${path}: expected <${tag}>, found ${describe(actual)}`;
+    }
+
+    const mismatch = compare(
+      children,
+      Array.from(actual.childNodes),
+      `${path} > ${tag}`
+    );
+
+    if (mismatch) {
+      return mismatch;
+    }
+
+    index++;
+  }
+
+  if (index < nodes.length) {
+    return `${path}: expected nothing, found ${describe(nodes[index])}`;
+  }
+}
+
+/**
+ * Warns about server rendered components whose markup does not match the
+ * structure their client code renders
+ */
+function checkHydration(container: Element) {
+  const walker = document.createTreeWalker(container, NodeFilter.SHOW_COMMENT);
+
+  for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+    const comment = node as Comment;
+
+    if (!comment.data.startsWith("rk:")) {
+      continue;
+    }
+
+    const key = comment.data.slice(3);
+    const expected = structures.get(key);
+    const name = key.slice(key.lastIndexOf(":") + 1);
+
+    if (!expected) {
+      continue;
+    }
+
+    const siblings = Array.from(comment.parentNode!.childNodes);
+    const start = siblings.indexOf(comment);
+    const end = skipRegion(siblings, start, "rk:", "/rk");
+    const mismatch = compare(expected, siblings.slice(start + 1, end - 1), name);
+
+    if (mismatch) {
+      console.warn(`Hydration mismatch in <${name}>, ${mismatch}`);
+    }
+  }
+}
+
+/**
+ * Removes the markers, keeping text nodes split where the client renders
+ * separate text children
+ */
+function removeHydrationMarkers(container: Element) {
+  const walker = document.createTreeWalker(container, NodeFilter.SHOW_COMMENT);
+  const markers: Node[] = [];
+
+  for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+    if (isMarker(node)) {
+      markers.push(node);
+    }
+  }
+
+  markers.forEach((marker) => marker.parentNode!.removeChild(marker));
+}
+
+/**
+ * Attaches to markup rendered on the server with the `ssr` target instead of
+ * re-creating the DOM
+ */
+export function hydrate(
+  ...params: Parameters<typeof infernoHydrate>
+): ReturnType<typeof infernoHydrate> {
+  const container = params[1] as Element;
+
+  if (!container) {
+    throw new Error("You need a target container");
+  }
+
+  if (import.meta.env.DEV) {
+    checkHydration(container);
+  }
+
+  removeHydrationMarkers(container);
+
+  return withBatchedEvents(() => infernoHydrate(...params));
+}
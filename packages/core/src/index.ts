@@ -2,11 +2,27 @@
 import "./types";
 
 export { render } from "./render";
+export { hydrate, registerHydration } from "./hydrate";
+export { bootIslands } from "./islands";
 export { useCleanup, useMountEffect } from "./component";
 export { createContext } from "./createContext";
 export { useState, assignState } from "./useState";
+export {
+  snapshotState,
+  restoreState,
+  resetState,
+  persistState,
+} from "./hookState";
 export { useAsync, Async } from "./useAsync";
+export { useAsyncSetup, AsyncFallbackContext } from "./useAsyncSetup";
+export { lazy, LazyComponent } from "./lazy";
 export { useAction, Action } from "./useAction";
+export {
+  callServerAction,
+  configureServerActions,
+  createActionHandler,
+  ActionHandlerOptions,
+} from "./serverActions";
 export { useSuspend } from "./useSuspend";
 export { useCatchError } from "./useCatchError";
 export { useRef, assignRef, Ref } from "./useRef";
@@ -14,5 +30,7 @@ export { useView } from "./useView";
 export { useEffect } from "./useEffect";
 export { useDerived, Derived } from "./useDerived";
 export { inspect } from "./inspect";
+export { bindText, bindAttrs } from "./bindings";
+export { mapList } from "./mapList";
 export { Router, useRouter } from "./useRouter";
 export { useLookup } from "./useLookup";
@@ -0,0 +1,75 @@
+import { createComponentVNode } from "./transformer";
+import { hydrate } from "./hydrate";
+
+export type IslandLoaders = Record<string, () => Promise<any>>;
+
+function whenVisible(island: Element, cb: () => void) {
+  // The boundary is `display: contents` and has no box, so its children are observed
+  const elements = Array.from(island.children);
+
+  if (!elements.length) {
+    cb();
+    return;
+  }
+
+  const observer = new IntersectionObserver((entries) => {
+    if (entries.some((entry) => entry.isIntersecting)) {
+      observer.disconnect();
+      cb();
+    }
+  });
+
+  elements.forEach((element) => observer.observe(element));
+}
+
+function whenIdle(cb: () => void) {
+  if ("requestIdleCallback" in window) {
+    requestIdleCallback(cb);
+  } else {
+    setTimeout(cb, 200);
+  }
+}
+
+/**
+ * Hydrates the islands of a server rendered page. The loaders are the
+ * `__raskIslands` export the SWC plugin generates for modules using
+ * `client:load`, `client:visible` or `client:idle`, each loading its
+ * component as a separate chunk.
+ *
+ * Components rendered without a directive are static HTML: they are not
+ * hydrated and none of their code is loaded unless a client module imports
+ * them.
+ */
+export function bootIslands(loaders: IslandLoaders) {
+  document
+    .querySelectorAll<HTMLElement>("rask-island:not([data-hydrated])")
+    .forEach((island) => {
+      const name = island.dataset.component!;
+      const load = loaders[name];
+
+      if (!load) {
+        console.warn(`There is no loader for the island <${name}>`);
+        return;
+      }
+
+      island.dataset.hydrated = "";
+
+      const start = () =>
+        load().then((component) => {
+          const props = JSON.parse(island.dataset.props || "{}");
+
+          hydrate(createComponentVNode(0, component, props, null, null), island);
+        });
+
+      switch (island.dataset.strategy) {
+        case "visible":
+          whenVisible(island, start);
+          break;
+        case "idle":
+          whenIdle(start);
+          break;
+        default:
+          start();
+      }
+    });
+}
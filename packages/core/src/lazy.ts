@@ -0,0 +1,40 @@
+import { createComponentVNode } from "./transformer";
+import { useAsyncSetup } from "./useAsyncSetup";
+
+export type LazyComponent<P> = ((props: P) => () => any) & {
+  /**
+   * Starts loading the component's module before it renders
+   */
+  preload(): Promise<unknown>;
+};
+
+/**
+ * Creates a component that loads its module the first time it renders,
+ * rendering the `AsyncFallbackContext` fallback until it is loaded. The SWC
+ * plugin compiles `/* @rask-lazy *\/` imports to `lazy` and records the
+ * loaded modules in the component manifest for preload hints.
+ *
+ * @example
+ * const Settings = lazy(() => import("./Settings"));
+ */
+export function lazy<P extends object>(
+  load: () => Promise<Record<string, any>>,
+  exportName = "default"
+): LazyComponent<P> {
+  let loading: Promise<Record<string, any>> | undefined;
+
+  const preload = () => (loading ??= load());
+
+  function Lazy(props: P) {
+    return useAsyncSetup(async () => {
+      const component = (await preload())[exportName];
+
+      // Spreading reads every prop, so changes are forwarded to the loaded component
+      return () => createComponentVNode(0, component, { ...props }, null, null);
+    });
+  }
+
+  Lazy.preload = preload;
+
+  return Lazy;
+}
@@ -0,0 +1,54 @@
+import { Component, createComponentVNode } from "inferno";
+import { VNodeFlags } from "inferno-vnode-flags";
+import { Reaction } from "mobx";
+
+// The SWC plugin compiles `items.map((item) => <Row />)` in render closures to
+// `mapList`. Every item renders in its own reactive boundary, so changing an
+// item renders that item again instead of the whole list.
+
+type ListItemProps<T> = {
+  item: T;
+  index: number;
+  render: (item: T, index: number) => any;
+};
+
+class ListItem<T> extends Component<ListItemProps<T>> {
+  private reaction = new Reaction("ListItem", () => this.forceUpdate());
+
+  componentWillUnmount() {
+    this.reaction.dispose();
+  }
+  render() {
+    let result: any = null;
+
+    this.reaction.track(() => {
+      result = this.props.render(this.props.item, this.props.index);
+    });
+
+    return result;
+  }
+}
+
+/**
+ * Renders each item of a list in its own reactive boundary, keyed by `key`
+ */
+export function mapList<T>(
+  items: T[],
+  render: (item: T, index: number) => any,
+  key?: (item: T, index: number) => any
+) {
+  // Only arrays are compiled to boundaries, other values keep their own `map`
+  if (!Array.isArray(items)) {
+    return (items as any).map(render);
+  }
+
+  return items.map((item, index) =>
+    createComponentVNode(
+      VNodeFlags.ComponentClass,
+      ListItem,
+      { item, index, render },
+      key ? key(item, index) : null,
+      null
+    )
+  );
+}
@@ -1,4 +1,5 @@
 import { createRequire } from "module";
+import { fileURLToPath } from "url";
 import type { Plugin } from "vite";
 
 const require = createRequire(import.meta.url);
@@ -10,6 +11,45 @@ export interface RaskPluginOptions {
    * In tests, you might want to use ".." to point to source files
    */
   importSource?: string;
+  /**
+   * What the compiled components render to
+   * Defaults to "ssr" for Vite's SSR transforms and "client" otherwise
+   */
+  target?: "client" | "ssr";
+  /**
+   * Emit hydration markers for the "ssr" target, and the structures
+   * hydration checks them against for the client
+   */
+  hydrate?: boolean;
+  /**
+   * Pass a call-site ID to the state hooks called in setup, so their state
+   * can be snapshotted and restored
+   */
+  hookIds?: boolean;
+  /**
+   * Compile "use server" functions to RPC stubs for the client and an action
+   * registry for the "ssr" target
+   */
+  serverActions?: boolean;
+  /**
+   * Compile reactive text children and attributes into bindings updating
+   * the DOM directly
+   */
+  bindings?: boolean;
+  /**
+   * Compile `.map` calls rendering an element per item into lists rendering
+   * each item on its own
+   */
+  lists?: boolean;
+  /**
+   * Attach a JSON manifest describing each module's components as a leading
+   * comment
+   */
+  manifest?: boolean;
+  /**
+   * Report why each candidate function was or wasn't compiled
+   */
+  explain?: boolean;
 }
 
 /**
@@ -21,6 +61,13 @@ export default function raskPlugin(options: RaskPluginOptions = {}): Plugin {
   const infernoPluginPath = require.resolve(
     "swc-plugin-inferno/swc_plugin_inferno.wasm"
   );
+  // The rask component plugin ships in this package, next to dist
+  const raskPluginPath = fileURLToPath(
+    new URL(
+      "../swc-plugin/target/wasm32-wasip1/release/swc_plugin_rask_component.wasm",
+      import.meta.url
+    )
+  );
 
   return {
     name: "rask-plugin",
@@ -39,7 +86,7 @@ export default function raskPlugin(options: RaskPluginOptions = {}): Plugin {
       };
     },
 
-    async transform(code: string, id: string) {
+    async transform(code: string, id: string, transformOptions) {
       // Only transform .tsx and .jsx files
       if (!/\.[tj]sx$/.test(id)) {
         return null;
@@ -48,7 +95,8 @@ export default function raskPlugin(options: RaskPluginOptions = {}): Plugin {
       // Use SWC for transformation
       const swc = await import("@swc/core");
 
-      // First, run the inferno plugin to transform JSX
+      // First, run the inferno plugin to transform JSX, then compile the
+      // components it rendered
       const result = await swc.transform(code, {
         filename: id,
         jsc: {
@@ -67,6 +115,23 @@ export default function raskPlugin(options: RaskPluginOptions = {}): Plugin {
                   defineAllArguments: false,
                 },
               ],
+              // Rask component compilation
+              [
+                raskPluginPath,
+                {
+                  importSource,
+                  target:
+                    options.target ??
+                    (transformOptions?.ssr ? "ssr" : "client"),
+                  hydrate: options.hydrate,
+                  hookIds: options.hookIds,
+                  serverActions: options.serverActions,
+                  bindings: options.bindings,
+                  lists: options.lists,
+                  manifest: options.manifest,
+                  explain: options.explain,
+                },
+              ],
             ],
           },
         },
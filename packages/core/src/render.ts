@@ -11,6 +11,14 @@ export function render(...params: Parameters<typeof infernoRender>) {
     throw new Error("You need a target container");
   }
 
+  return withBatchedEvents(() => infernoRender(...params));
+}
+
+/**
+ * Runs a render into the DOM, wrapping the event listeners Inferno
+ * delegates to the document with syncBatch
+ */
+export function withBatchedEvents<T>(fn: () => T): T {
   /**
    * Temporarily patches document.addEventListener during render to capture
    * and wrap Inferno's delegated event listeners with syncBatch
@@ -67,7 +75,7 @@ export function render(...params: Parameters<typeof infernoRender>) {
 
   try {
     // Call render - Inferno will synchronously attach its listeners
-    return infernoRender(...params);
+    return fn();
   } finally {
     // Restore original addEventListener
     document.addEventListener = originalAddEventListener;
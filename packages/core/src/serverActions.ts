@@ -0,0 +1,112 @@
+// The SWC plugin's `serverActions` option replaces functions marked with
+// "use server" by stubs calling `callServerAction` with a stable action ID,
+// built from the file and export name. The `ssr` target exports the original
+// functions by ID as `__raskActions`, which `createActionHandler` serves.
+
+export type ServerAction = (...args: any[]) => Promise<unknown>;
+
+export type ServerActionOptions = {
+  /**
+   * Path the actions are served from, the action ID is appended to it
+   */
+  endpoint: string;
+  fetch: typeof fetch;
+};
+
+const options: ServerActionOptions = {
+  endpoint: "/_rask/actions",
+  fetch: (...args) => fetch(...args),
+};
+
+export function configureServerActions(next: Partial<ServerActionOptions>) {
+  Object.assign(options, next);
+}
+
+/**
+ * Calls a server action with JSON serialized arguments, rejecting with the
+ * error message of the server when it throws
+ */
+export async function callServerAction(
+  id: string,
+  args: unknown[]
+): Promise<unknown> {
+  const response = await options.fetch(
+    `${options.endpoint}/${encodeURIComponent(id)}`,
+    {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(args),
+    }
+  );
+  const payload = await response.json();
+
+  if (!response.ok) {
+    throw new Error(
+      payload.error ?? `Server action ${id} failed with ${response.status}`
+    );
+  }
+
+  return payload.result;
+}
+
+function json(body: unknown, status: number) {
+  return new Response(JSON.stringify(body), {
+    status,
+    headers: { "Content-Type": "application/json" },
+  });
+}
+
+export type ActionHandlerOptions = {
+  /**
+   * Send the message of errors thrown by actions to the client. Messages can
+   * contain server details, so they are replaced by a generic error by default
+   */
+  exposeErrors?: boolean;
+};
+
+/**
+ * Creates a fetch style handler running the actions of the given registries,
+ * the `__raskActions` exports of the server build
+ */
+export function createActionHandler(
+  registries: Record<string, ServerAction> | Record<string, ServerAction>[],
+  handlerOptions: ActionHandlerOptions = {}
+) {
+  const actions: Record<string, ServerAction> = Object.assign(
+    {},
+    ...(Array.isArray(registries) ? registries : [registries])
+  );
+
+  return async (request: Request): Promise<Response> => {
+    if (request.method !== "POST") {
+      return json({ error: "Server actions are called with POST" }, 405);
+    }
+
+    const id = decodeURIComponent(
+      new URL(request.url).pathname.split("/").pop() ?? ""
+    );
+    const action = Object.prototype.hasOwnProperty.call(actions, id)
+      ? actions[id]
+      : undefined;
+
+    if (!action) {
+      return json({ error: `Unknown server action ${id}` }, 404);
+    }
+
+    try {
+      const args = await request.json();
+      const result = await action(...(Array.isArray(args) ? args : []));
+
+      return json({ result: result ?? null }, 200);
+    } catch (error) {
+      if (!handlerOptions.exposeErrors) {
+        return json({ error: `Server action ${id} failed` }, 500);
+      }
+
+      return json(
+        { error: error instanceof Error ? error.message : String(error) },
+        500
+      );
+    }
+  };
+}
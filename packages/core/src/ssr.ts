@@ -0,0 +1,301 @@
+import { RaskComponent, withCurrentComponent } from "./component";
+import { resetState } from "./hookState";
+
+// Helpers used by the output of the SWC plugin's `target: "ssr"` mode, which
+// compiles render output to string concatenation instead of VNodes
+
+export const VOID_ELEMENTS = new Set([
+  "area",
+  "base",
+  "br",
+  "col",
+  "embed",
+  "hr",
+  "img",
+  "input",
+  "link",
+  "meta",
+  "source",
+  "track",
+  "wbr",
+]);
+
+export const BOOLEAN_ATTRIBUTES = new Set([
+  "allowfullscreen",
+  "async",
+  "autofocus",
+  "autoplay",
+  "checked",
+  "controls",
+  "default",
+  "defer",
+  "disabled",
+  "formnovalidate",
+  "hidden",
+  "inert",
+  "loop",
+  "multiple",
+  "muted",
+  "nomodule",
+  "novalidate",
+  "open",
+  "playsinline",
+  "readonly",
+  "required",
+  "reversed",
+  "selected",
+]);
+
+/**
+ * Markup that is already rendered and must not be escaped again
+ */
+export class SafeHtml {
+  constructor(readonly html: string) {}
+  toString() {
+    return this.html;
+  }
+}
+
+export function ssrHtml(html: string) {
+  return new SafeHtml(html);
+}
+
+const ESCAPED: Record<string, string> = {
+  "&": "&amp;",
+  "<": "&lt;",
+  ">": "&gt;",
+  '"': "&quot;",
+  "'": "&#39;",
+};
+
+export function escapeHtml(value: string) {
+  return value.replace(/[&<>"']/g, (char) => ESCAPED[char]);
+}
+
+/**
+ * Renders a dynamic child, which like in Inferno can be text, rendered
+ * markup, an array of children or an empty value
+ */
+export function ssrChild(child: unknown): string {
+  if (child instanceof SafeHtml) {
+    return child.html;
+  }
+
+  if (child == null || typeof child === "boolean") {
+    return "";
+  }
+
+  if (Array.isArray(child)) {
+    return child.map(ssrChild).join("");
+  }
+
+  return escapeHtml(String(child));
+}
+
+function hyphenate(name: string) {
+  return name.replace(/[A-Z]/g, (char) => "-" + char.toLowerCase());
+}
+
+export function ssrStyle(style: unknown): string {
+  if (style == null || typeof style !== "object") {
+    return style == null ? "" : String(style);
+  }
+
+  return Object.entries(style)
+    .filter(([, value]) => value != null && value !== "")
+    .map(([name, value]) => `${hyphenate(name)}:${value}`)
+    .join(";");
+}
+
+function attributeName(name: string) {
+  if (name === "className") {
+    return "class";
+  }
+
+  if (name === "htmlFor") {
+    return "for";
+  }
+
+  return name;
+}
+
+function isRenderedAttribute(name: string) {
+  return (
+    name !== "key" &&
+    name !== "ref" &&
+    name !== "children" &&
+    name !== "dangerouslySetInnerHTML" &&
+    !name.startsWith("$") &&
+    !/^on[A-Z]/.test(name)
+  );
+}
+
+/**
+ * Renders a single attribute with its leading space, or nothing when the
+ * value removes the attribute
+ */
+export function ssrAttr(name: string, value: unknown): string {
+  if (!isRenderedAttribute(name) || typeof value === "function") {
+    return "";
+  }
+
+  name = attributeName(name);
+
+  if (name === "style") {
+    const style = ssrStyle(value);
+
+    return style ? ` style="${escapeHtml(style)}"` : "";
+  }
+
+  if (BOOLEAN_ATTRIBUTES.has(name.toLowerCase())) {
+    return value ? ` ${name}` : "";
+  }
+
+  if (value == null) {
+    return "";
+  }
+
+  if (value === false && !/^(aria|data)-/.test(name)) {
+    return "";
+  }
+
+  return ` ${name}="${escapeHtml(String(value))}"`;
+}
+
+export function ssrAttrs(props: Record<string, unknown> | null | undefined) {
+  let html = "";
+
+  for (const name in props) {
+    html += ssrAttr(name, props[name]);
+  }
+
+  return html;
+}
+
+/**
+ * Renders the `__html` of a `dangerouslySetInnerHTML` prop without escaping,
+ * as Inferno sets it as the element content in place of the children
+ */
+export function ssrInnerHtml(value: unknown): string {
+  const html = (value as { __html?: unknown } | null | undefined)?.__html;
+
+  return html == null ? "" : String(html);
+}
+
+/**
+ * Renders an element whose tag is only known at runtime
+ */
+export function ssrElement(
+  tag: string,
+  className: unknown,
+  props: Record<string, unknown> | null | undefined,
+  children: unknown
+): string {
+  const html = `<${tag}${ssrAttr("className", className)}${ssrAttrs(props)}>`;
+
+  if (VOID_ELEMENTS.has(tag)) {
+    return html;
+  }
+
+  if (props?.dangerouslySetInnerHTML != null) {
+    return `${html}${ssrInnerHtml(props.dangerouslySetInnerHTML)}</${tag}>`;
+  }
+
+  return `${html}${ssrChild(children ?? props?.children)}</${tag}>`;
+}
+
+let parentComponent: RaskComponent<any> | undefined;
+
+/**
+ * Runs the setup of a component and renders it to a string. Effects are not
+ * run and the component is cleaned up right after rendering. With `markers`
+ * the output is wrapped in the component boundary `hydrate` checks against.
+ */
+export function ssrComponent(
+  component: any,
+  props: any = {},
+  markers = false
+): string {
+  const html = renderComponent(component, props);
+
+  if (!markers) {
+    return html;
+  }
+
+  // The compiler keys components by file, so components of the same name in
+  // different files are told apart
+  const key = component.hydrationKey ?? component.name;
+
+  return `<!--rk:${key}-->${html}<!--/rk-->`;
+}
+
+/**
+ * Renders a component marked with a `client:*` directive inside an island
+ * boundary that `bootIslands` hydrates on the client. Props that can not be
+ * serialized, like functions and children, are not passed to the client.
+ */
+export function ssrIsland(
+  component: any,
+  props: Record<string, unknown>,
+  strategy: string,
+  name: string
+): string {
+  const clientProps = Object.fromEntries(
+    Object.entries(props).filter(
+      ([key, value]) => key !== "children" && typeof value !== "function"
+    )
+  );
+
+  return `<rask-island data-component="${escapeHtml(
+    name
+  )}" data-strategy="${escapeHtml(strategy)}" data-props="${escapeHtml(
+    JSON.stringify(clientProps)
+  )}" style="display:contents">${ssrComponent(
+    component,
+    props,
+    true
+  )}</rask-island>`;
+}
+
+function renderComponent(component: any, props: any): string {
+  const instance = new RaskComponent<any>(
+    props,
+    parentComponent
+      ? { getContext: parentComponent.getContext.bind(parentComponent) }
+      : {}
+  );
+  instance.isServer = true;
+
+  const previousParent = parentComponent;
+  parentComponent = instance;
+
+  try {
+    const renderFn = withCurrentComponent(instance, () => component(props));
+
+    if (typeof renderFn !== "function") {
+      return ssrChild(renderFn);
+    }
+
+    instance.isRendering = true;
+
+    return ssrChild(withCurrentComponent(instance, () => renderFn(props)));
+  } finally {
+    parentComponent = previousParent;
+    instance.componentWillUnmount();
+  }
+}
+
+/**
+ * Renders a component compiled with the `ssr` target to an HTML string. Use
+ * `hydrate` when the markup is hydrated on the client. The states of the page
+ * can be serialized with `snapshotState` afterwards.
+ */
+export function renderToString(
+  component: any,
+  props: any = {},
+  options: { hydrate?: boolean } = {}
+): string {
+  resetState();
+
+  return ssrComponent(component, props, options.hydrate);
+}
@@ -0,0 +1,145 @@
+import { describe, it, expect } from "vitest";
+import { bindText, bindAttrs } from "../bindings";
+import { useState } from "../useState";
+import { render } from "../index";
+
+describe("bindings", () => {
+  it("should update a text binding without rendering the component again", async () => {
+    let state!: { count: number };
+    let renders = 0;
+
+    // What the plugin emits for `<p>Count: {state.count}</p>`
+    function Counter() {
+      state = useState({ count: 0 });
+      return () => {
+        renders++;
+        return <p>Count: {bindText(() => state.count)}</p>;
+      };
+    }
+
+    const container = document.createElement("div");
+    render(<Counter />, container);
+
+    const text = container.querySelector("p")!.lastChild;
+
+    expect(container.textContent).toBe("Count: 0");
+
+    state.count++;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Count: 1");
+    expect(container.querySelector("p")!.lastChild).toBe(text);
+    expect(renders).toBe(1);
+  });
+
+  it("should render values that are not text", async () => {
+    let state!: { label: any };
+
+    function Label() {
+      state = useState<{ label: any }>({ label: "Text" });
+      return () => <div>{bindText(() => state.label)}</div>;
+    }
+
+    const container = document.createElement("div");
+    render(<Label />, container);
+
+    state.label = <b>Bold</b>;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.innerHTML).toBe("<div><b>Bold</b></div>");
+
+    state.label = "Text";
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.innerHTML).toBe("<div>Text</div>");
+  });
+
+  it("should update bound attributes and forward the element to the ref", async () => {
+    let state!: { active: boolean; title: string | null };
+    let element: Element | null = null;
+    let renders = 0;
+
+    // What the plugin emits for `<div className={...} title={state.title} ref={...} />`
+    function Item() {
+      state = useState({ active: false, title: "Item" as string | null });
+      const ref = (node: Element | null) => (element = node);
+      return () => {
+        renders++;
+        return (
+          <div
+            ref={bindAttrs(
+              {
+                className: () => (state.active ? "active" : "idle"),
+                title: () => state.title,
+              },
+              ref
+            )}
+          />
+        );
+      };
+    }
+
+    const container = document.createElement("div");
+    render(<Item />, container);
+
+    const div = container.querySelector("div")!;
+
+    expect(element).toBe(div);
+    expect(div.className).toBe("idle");
+    expect(div.getAttribute("title")).toBe("Item");
+
+    state.active = true;
+    state.title = null;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(div.className).toBe("active");
+    expect(div.hasAttribute("title")).toBe(false);
+    expect(renders).toBe(1);
+  });
+
+  it("should keep the attribute bindings when the component renders again", async () => {
+    let state!: { active: boolean; label: string };
+    let reads = 0;
+
+    function Item() {
+      state = useState({ active: false, label: "One" });
+      return () => (
+        <div
+          ref={bindAttrs({
+            className: () => {
+              reads++;
+              return state.active ? "active" : "idle";
+            },
+          })}
+        >
+          {state.label}
+        </div>
+      );
+    }
+
+    const container = document.createElement("div");
+    render(<Item />, container);
+
+    // Renders again with a new ref
+    state.label = "Two";
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Two");
+
+    reads = 0;
+    state.active = true;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.querySelector("div")!.className).toBe("active");
+    expect(reads).toBe(1);
+
+    render(null, container);
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    reads = 0;
+    state.active = false;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(reads).toBe(0);
+  });
+});
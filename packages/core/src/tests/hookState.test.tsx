@@ -0,0 +1,54 @@
+import { describe, it, expect } from "vitest";
+import { useState } from "../useState";
+import { render } from "../index";
+import { restoreState, snapshotState, resetState } from "../hookState";
+
+describe("hook state", () => {
+  it("should snapshot states created with a call-site ID", () => {
+    resetState();
+
+    function Counter() {
+      const state = useState({ count: 1 }, "abc:Counter:0");
+      state.count++;
+      return () => <div>{state.count}</div>;
+    }
+
+    const container = document.createElement("div");
+    render(
+      <>
+        <Counter />
+        <Counter />
+      </>,
+      container
+    );
+
+    expect(snapshotState()).toEqual({
+      "abc:Counter:0#0": { count: 2 },
+      "abc:Counter:0#1": { count: 2 },
+    });
+  });
+
+  it("should restore states in creation order", () => {
+    resetState();
+    restoreState({
+      "abc:Counter:0#0": { count: 5 },
+      "abc:Counter:0#1": { count: 7 },
+    });
+
+    function Counter() {
+      const state = useState({ count: 0 }, "abc:Counter:0");
+      return () => <span>{state.count}</span>;
+    }
+
+    const container = document.createElement("div");
+    render(
+      <>
+        <Counter />
+        <Counter />
+      </>,
+      container
+    );
+
+    expect(container.textContent).toBe("57");
+  });
+});
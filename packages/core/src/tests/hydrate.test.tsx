@@ -0,0 +1,43 @@
+import { describe, it, expect, vi } from "vitest";
+import { hydrate, registerHydration } from "../hydrate";
+import { useState } from "../useState";
+
+describe("hydrate", () => {
+  it("should attach to server rendered markup", () => {
+    function Counter() {
+      const state = useState({ count: 1 });
+      return () => <p>Count: {state.count}</p>;
+    }
+
+    const container = document.createElement("div");
+    container.innerHTML =
+      "<!--rk:Counter--><p>Count: <!--[-->1<!--]--></p><!--/rk-->";
+    const paragraph = container.querySelector("p");
+
+    hydrate(<Counter />, container);
+
+    expect(container.querySelector("p")).toBe(paragraph);
+    expect(container.innerHTML).toBe("<p>Count: 1</p>");
+  });
+
+  it("should warn when the markup does not match the component", () => {
+    const warn = vi.spyOn(console, "warn").mockImplementation(() => {});
+
+    function Title() {
+      return () => <h1>Title</h1>;
+    }
+
+    registerHydration("8eee3be9:Title", JSON.stringify([["h1", "Title"]]));
+    registerHydration("1a2b3c4d:Title", JSON.stringify([["h2", "Title"]]));
+
+    const container = document.createElement("div");
+    container.innerHTML = "<!--rk:8eee3be9:Title--><h2>Title</h2><!--/rk-->";
+
+    hydrate(<Title />, container);
+
+    expect(warn).toHaveBeenCalledWith(
+      "Hydration mismatch in <Title>, Title: expected <h1>, found <h2>"
+    );
+    warn.mockRestore();
+  });
+});
@@ -0,0 +1,52 @@
+import { describe, it, expect, vi } from "vitest";
+import { lazy } from "../lazy";
+import { useState } from "../useState";
+import { render } from "../index";
+
+describe("lazy", () => {
+  function Greeting(props: { name: string }) {
+    return () => <h1>Hello {props.name}</h1>;
+  }
+
+  it("should render the loaded component and forward prop changes", async () => {
+    const load = vi.fn(() => Promise.resolve({ Greeting }));
+    const LazyGreeting = lazy<{ name: string }>(load, "Greeting");
+
+    let state!: { name: string };
+
+    function App() {
+      state = useState({ name: "Rask" });
+      return () => <LazyGreeting name={state.name} />;
+    }
+
+    const container = document.createElement("div");
+    render(<App />, container);
+
+    expect(container.textContent).toBe("");
+
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Hello Rask");
+
+    state.name = "Inferno";
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Hello Inferno");
+    expect(load).toHaveBeenCalledTimes(1);
+  });
+
+  it("should load the module once when preloaded", async () => {
+    const load = vi.fn(() => Promise.resolve({ default: Greeting }));
+    const LazyGreeting = lazy<{ name: string }>(load);
+
+    await LazyGreeting.preload();
+
+    const container = document.createElement("div");
+    render(<LazyGreeting name="Rask" />, container);
+
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Hello Rask");
+    expect(load).toHaveBeenCalledTimes(1);
+  });
+});
@@ -0,0 +1,103 @@
+import { describe, it, expect } from "vitest";
+import { mapList } from "../mapList";
+import { useState } from "../useState";
+import { render } from "../index";
+
+type Todo = { id: number; text: string; done: boolean };
+
+describe("mapList", () => {
+  it("should render a changed item without rendering the list again", async () => {
+    let state!: { todos: Todo[] };
+    let listRenders = 0;
+    let itemRenders = 0;
+
+    // What the plugin emits for `state.todos.map((todo) => <li key={todo.id}>...</li>)`
+    function TodoList() {
+      state = useState({
+        todos: [
+          { id: 1, text: "Learn RASK", done: false },
+          { id: 2, text: "Build app", done: false },
+        ],
+      });
+      return () => {
+        listRenders++;
+        return (
+          <ul>
+            {mapList(
+              state.todos,
+              (todo) => {
+                itemRenders++;
+                return (
+                  <li key={todo.id}>
+                    {todo.done ? "x" : "o"} {todo.text}
+                  </li>
+                );
+              },
+              (todo) => todo.id
+            )}
+          </ul>
+        );
+      };
+    }
+
+    const container = document.createElement("div");
+    render(<TodoList />, container);
+
+    expect(container.textContent).toBe("o Learn RASKo Build app");
+    expect(listRenders).toBe(1);
+    expect(itemRenders).toBe(2);
+
+    state.todos[1].done = true;
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("o Learn RASKx Build app");
+    expect(listRenders).toBe(1);
+    expect(itemRenders).toBe(3);
+  });
+
+  it("should keep the elements of moved items", async () => {
+    let state!: { todos: Todo[] };
+
+    function TodoList() {
+      state = useState({
+        todos: [
+          { id: 1, text: "First", done: false },
+          { id: 2, text: "Second", done: false },
+        ],
+      });
+      return () => (
+        <ul>
+          {mapList(
+            state.todos,
+            (todo, index) => (
+              <li>
+                {index}: {todo.text}
+              </li>
+            ),
+            (todo) => todo.id
+          )}
+        </ul>
+      );
+    }
+
+    const container = document.createElement("div");
+    render(<TodoList />, container);
+
+    const [first, second] = Array.from(container.querySelectorAll("li"));
+
+    state.todos = [state.todos[1], state.todos[0]];
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    const [moved, other] = Array.from(container.querySelectorAll("li"));
+
+    expect(moved).toBe(second);
+    expect(other).toBe(first);
+    expect(container.textContent).toBe("0: Second1: First");
+  });
+
+  it("should map values that are not arrays with their own map", () => {
+    const items = { map: (render: (item: number) => number) => [render(1)] };
+
+    expect(mapList(items as any, (item: number) => item + 1)).toEqual([2]);
+  });
+});
@@ -0,0 +1,81 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  callServerAction,
+  configureServerActions,
+  createActionHandler,
+} from "../serverActions";
+import { useAction } from "../useAction";
+
+// What the plugin emits for `export async function addTodo(text) { "use server"; ... }`
+const todos: string[] = [];
+
+const __raskActions = {
+  "abc:addTodo": async (text: string) => {
+    todos.push(text);
+    return todos.length;
+  },
+  "abc:failing": async () => {
+    throw new Error("No todo");
+  },
+};
+
+function addTodo(...args: [string]) {
+  return callServerAction("abc:addTodo", args) as Promise<number>;
+}
+
+describe("server actions", () => {
+  beforeEach(() => {
+    todos.length = 0;
+
+    // A local stand-in for the server
+    const handler = createActionHandler(__raskActions, {
+      exposeErrors: true,
+    });
+
+    configureServerActions({
+      fetch: (input, init) =>
+        handler(new Request(new URL(String(input), "http://localhost"), init)),
+    });
+  });
+
+  it("should call the action registered with the ID", async () => {
+    await expect(addTodo("Write tests")).resolves.toBe(1);
+    expect(todos).toEqual(["Write tests"]);
+  });
+
+  it("should reject with the error thrown on the server", async () => {
+    await expect(callServerAction("abc:failing", [])).rejects.toThrow(
+      "No todo"
+    );
+    await expect(callServerAction("abc:missing", [])).rejects.toThrow(
+      "Unknown server action abc:missing"
+    );
+  });
+
+  it("should only send error messages to the client when exposed", async () => {
+    const handler = createActionHandler([__raskActions]);
+
+    configureServerActions({
+      fetch: (input, init) =>
+        handler(new Request(new URL(String(input), "http://localhost"), init)),
+    });
+
+    await expect(callServerAction("abc:failing", [])).rejects.toThrow(
+      "Server action abc:failing failed"
+    );
+  });
+
+  it("should work with useAction", async () => {
+    const [state, run] = useAction(addTodo);
+
+    run("From an action");
+
+    expect(state.isPending).toBe(true);
+
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(state.isPending).toBe(false);
+    expect(state.result).toBe(1);
+    expect(todos).toEqual(["From an action"]);
+  });
+});
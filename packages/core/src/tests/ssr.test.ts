@@ -0,0 +1,119 @@
+import { describe, it, expect, vi } from "vitest";
+import {
+  renderToString,
+  ssrAttr,
+  ssrAttrs,
+  ssrChild,
+  ssrComponent,
+  ssrElement,
+  ssrHtml,
+  ssrInnerHtml,
+  ssrIsland,
+} from "../ssr";
+import { useState } from "../useState";
+import { useEffect } from "../useEffect";
+import { useMountEffect } from "../component";
+import { createContext } from "../createContext";
+
+describe("ssr", () => {
+  it("should escape text children but not rendered markup", () => {
+    expect(ssrChild('<b>"hi"</b>')).toBe("&lt;b&gt;&quot;hi&quot;&lt;/b&gt;");
+    expect(ssrChild(ssrHtml("<b>hi</b>"))).toBe("<b>hi</b>");
+    expect(ssrChild(["a", 1, null, false, ssrHtml("<br>")])).toBe("a1<br>");
+  });
+
+  it("should render attributes", () => {
+    expect(ssrAttr("className", "a&b")).toBe(' class="a&amp;b"');
+    expect(ssrAttr("htmlFor", "name")).toBe(' for="name"');
+    expect(ssrAttr("disabled", true)).toBe(" disabled");
+    expect(ssrAttr("disabled", false)).toBe("");
+    expect(ssrAttr("aria-hidden", false)).toBe(' aria-hidden="false"');
+    expect(ssrAttr("onClick", () => {})).toBe("");
+    expect(ssrAttr("style", { fontSize: "12px", color: "red" })).toBe(
+      ' style="font-size:12px;color:red"'
+    );
+    expect(ssrAttrs({ id: "x", key: 1, title: null })).toBe(' id="x"');
+  });
+
+  it("should render void elements without closing tags", () => {
+    expect(ssrElement("img", null, { src: "/a.png" }, null)).toBe(
+      '<img src="/a.png">'
+    );
+    expect(ssrElement("p", "text", null, "hi")).toBe('<p class="text">hi</p>');
+  });
+
+  it("should render dangerouslySetInnerHTML unescaped in place of the children", () => {
+    const props = {
+      id: "post",
+      dangerouslySetInnerHTML: { __html: "<b>hi</b>" },
+    };
+
+    expect(ssrElement("div", null, props, "ignored")).toBe(
+      '<div id="post"><b>hi</b></div>'
+    );
+    expect(ssrInnerHtml({ __html: "<i>a & b</i>" })).toBe("<i>a & b</i>");
+    expect(ssrInnerHtml(null)).toBe("");
+  });
+
+  it("should run setup without running effects", () => {
+    const effectFn = vi.fn();
+    const mountFn = vi.fn();
+
+    function Component(props: { name: string }) {
+      const state = useState({ count: 1 });
+      useEffect(effectFn);
+      useMountEffect(mountFn);
+      return () => ssrHtml(`<h1>${props.name} ${state.count}</h1>`);
+    }
+
+    expect(renderToString(Component, { name: "Rask" })).toBe(
+      "<h1>Rask 1</h1>"
+    );
+    expect(effectFn).not.toHaveBeenCalled();
+    expect(mountFn).not.toHaveBeenCalled();
+  });
+
+  it("should provide contexts to child components", () => {
+    const ThemeContext = createContext(() => ({ color: "blue" }));
+
+    function Child() {
+      const theme = ThemeContext.use();
+      return () => ssrHtml(`<span>${theme.color}</span>`);
+    }
+
+    function Parent() {
+      ThemeContext.inject();
+      return () => ssrHtml(`<div>${ssrComponent(Child)}</div>`);
+    }
+
+    expect(renderToString(Parent)).toBe("<div><span>blue</span></div>");
+  });
+
+  it("should mark component boundaries with the compiled hydration key", () => {
+    function Title() {
+      return () => ssrHtml("<h1>Title</h1>");
+    }
+
+    expect(ssrComponent(Title, {}, true)).toBe(
+      "<!--rk:Title--><h1>Title</h1><!--/rk-->"
+    );
+
+    Object.assign(Title, { hydrationKey: "8eee3be9:Title" });
+
+    expect(ssrComponent(Title, {}, true)).toBe(
+      "<!--rk:8eee3be9:Title--><h1>Title</h1><!--/rk-->"
+    );
+  });
+
+  it("should render islands with their serializable props", () => {
+    function Counter(props: { start: number }) {
+      return () => ssrHtml(`<button>${props.start}</button>`);
+    }
+
+    expect(
+      ssrIsland(Counter, { start: 1, onChange: () => {} }, "visible", "Counter")
+    ).toBe(
+      '<rask-island data-component="Counter" data-strategy="visible" data-props="{&quot;start&quot;:1}" style="display:contents"><!--rk:Counter--><button>1</button><!--/rk--></rask-island>'
+    );
+  });
+});
@@ -0,0 +1,47 @@
+import { describe, it, expect } from "vitest";
+import { useAsyncSetup, AsyncFallbackContext } from "../useAsyncSetup";
+import { useState } from "../useState";
+import { render } from "../index";
+
+describe("useAsyncSetup", () => {
+  // What the plugin emits for `async function Profile() { ... }`
+  function Profile(props: { name: string }) {
+    return useAsyncSetup(async () => {
+      const state = useState({ visits: 1 });
+      const user = await Promise.resolve({ name: props.name });
+
+      return () => (
+        <p>
+          {user.name} {state.visits}
+        </p>
+      );
+    });
+  }
+
+  it("should render the fallback until the setup resolves", async () => {
+    function App() {
+      AsyncFallbackContext.inject(() => <span>Loading</span>);
+      return () => <Profile name="Rask" />;
+    }
+
+    const container = document.createElement("div");
+    render(<App />, container);
+
+    expect(container.textContent).toBe("Loading");
+
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Rask 1");
+  });
+
+  it("should render nothing while pending without a fallback", async () => {
+    const container = document.createElement("div");
+    render(<Profile name="Rask" />, container);
+
+    expect(container.textContent).toBe("");
+
+    await new Promise((resolve) => setTimeout(resolve, 10));
+
+    expect(container.textContent).toBe("Rask 1");
+  });
+});
@@ -32,14 +32,28 @@ export type Action<T, P = null> = [
 ];
 
 export function useAction<T, P = null>(
-  fn: [P] extends [null] ? () => Promise<T> : (params: P) => Promise<T>
+  fn: [P] extends [null] ? () => Promise<T> : (params: P) => Promise<T>,
+  id?: string
 ): Action<T, P> {
-  const state = useState<ActionState<T, P>>({
-    isPending: false,
-    error: null,
-    params: null,
-    result: null,
-  });
+  const state = useState<ActionState<T, P>>(
+    {
+      isPending: false,
+      error: null,
+      params: null,
+      result: null,
+    },
+    id
+  );
+
+  // A request pending when the state was snapshotted does not resume
+  if (state.isPending) {
+    assignState(state, {
+      isPending: false,
+      error: null,
+      params: null,
+      result: null,
+    });
+  }
 
   let abortController: AbortController | undefined;
 
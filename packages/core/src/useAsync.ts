@@ -2,6 +2,7 @@ import { useCleanup, getCurrentComponent } from "./component";
 import { transaction } from "./scheduler";
 import { assignState, useState } from "./useState";
 import { Reaction } from "mobx";
+import { takeRestoredState, trackState } from "./hookState";
 
 export type AsyncState<T> =
   | {
@@ -54,7 +55,8 @@ export function isAsync(value: unknown) {
 }
 
 export function useAsync<T extends NonNullable<any>>(
-  fn: (signal?: AbortSignal) => Promise<T>
+  fn: (signal?: AbortSignal) => Promise<T>,
+  id?: string
 ) {
   const currentComponent = getCurrentComponent();
 
@@ -62,12 +64,30 @@ export function useAsync<T extends NonNullable<any>>(
     throw new Error("Only use useAsync in component setup");
   }
 
-  const state = useState<AsyncState<T>>({
-    isLoading: true,
-    isRefreshing: false,
-    value: null,
-    error: null,
-  });
+  const restored = id
+    ? (takeRestoredState(id) as AsyncState<T> | undefined)
+    : undefined;
+
+  // A restored value is shown while it is refreshed
+  const state = useState<AsyncState<T>>(
+    restored?.value != null
+      ? {
+          isLoading: false,
+          isRefreshing: true,
+          value: restored.value,
+          error: null,
+        }
+      : {
+          isLoading: true,
+          isRefreshing: false,
+          value: null,
+          error: null,
+        }
+  );
+
+  if (id) {
+    trackState(id, state);
+  }
 
   const refreshResolvers: Array<{
     resolve: () => void;
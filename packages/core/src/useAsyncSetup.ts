@@ -0,0 +1,59 @@
+import { untracked } from "mobx";
+import { getCurrentComponent } from "./component";
+import { createContext } from "./createContext";
+import { useAsync } from "./useAsync";
+
+/**
+ * Provides what components with an async setup render while their setup is
+ * pending, to all child components
+ *
+ * @example
+ * function App() {
+ *   AsyncFallbackContext.inject(() => <Spinner />);
+ *   return () => <Profile />;
+ * }
+ */
+export const AsyncFallbackContext = createContext(
+  (fallback: () => any) => fallback
+);
+
+// Components without a parent providing a fallback render nothing
+function useFallback(): () => any {
+  try {
+    return AsyncFallbackContext.use();
+  } catch {
+    return () => null;
+  }
+}
+
+/**
+ * Runs an async setup, as the SWC plugin compiles
+ * `async function Profile() { const user = await load(); return () => <div /> }`.
+ * Hooks have to be called before the first `await`, while the component is
+ * still being set up.
+ */
+export function useAsyncSetup(setup: () => Promise<() => any>) {
+  const currentComponent = getCurrentComponent();
+
+  if (!currentComponent || currentComponent.isRendering) {
+    throw new Error("Only use useAsyncSetup in component setup");
+  }
+
+  const fallback = useFallback();
+
+  // The setup runs once, reading props or state in it must not run it again
+  const [state] = useAsync(() => untracked(setup));
+
+  return () => {
+    if (state.error) {
+      // Reported to the nearest useCatchError
+      throw state.error;
+    }
+
+    if (state.isLoading) {
+      return fallback();
+    }
+
+    return state.value!();
+  };
+}
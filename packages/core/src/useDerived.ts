@@ -5,8 +5,11 @@ export type Derived<T extends Record<string, () => any>> = {
   [K in keyof T]: ReturnType<T[K]>;
 };
 
+// The call-site ID injected by the SWC plugin is accepted, derived values are
+// recomputed from restored state rather than restored themselves
 export function useDerived<T extends Record<string, () => any>>(
-  computedDefs: T
+  computedDefs: T,
+  _id?: string
 ): Derived<T> {
   const currentComponent = getCurrentComponent();
 
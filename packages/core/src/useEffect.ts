@@ -7,6 +7,10 @@ export function useEffect(cb: () => void | (() => void)) {
     throw new Error("Only use useEffect in component setup");
   }
 
+  if (component.isServer) {
+    return;
+  }
+
   let disposer: (() => void) | void;
 
   const autorunDisposer = autorun(() => {
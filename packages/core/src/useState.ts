@@ -1,4 +1,5 @@
 import { observable } from "mobx";
+import { takeRestoredState, trackState } from "./hookState";
 
 export function assignState<T extends object>(state: T, newState: T) {
   return Object.assign(state, newState);
@@ -26,8 +27,20 @@ export function assignState<T extends object>(state: T, newState: T) {
  * }
  *
  * @param state - The initial state object to make reactive
+ * @param id - Call-site ID injected by the SWC plugin, used to snapshot and restore the state
  * @returns A reactive proxy of the state object
  */
-export function useState<T extends object>(state: T): T {
-  return observable(state);
+export function useState<T extends object>(state: T, id?: string): T {
+  if (!id) {
+    return observable(state);
+  }
+
+  const restored = takeRestoredState(id);
+  const value = observable(
+    restored === undefined ? state : Object.assign({}, state, restored)
+  );
+
+  trackState(id, value);
+
+  return value;
 }
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use swc_core::ecma::ast::*;
+
+/// Array, Map and Set methods that mutate their receiver
+pub const MUTATING_METHODS: &[&str] = &[
+    "push",
+    "pop",
+    "shift",
+    "unshift",
+    "splice",
+    "sort",
+    "reverse",
+    "fill",
+    "copyWithin",
+    "set",
+    "add",
+    "delete",
+    "clear",
+];
+
+/// Hooks exported by rask-ui that may only be called during component setup
+pub const SETUP_HOOKS: &[&str] = &[
+    "useState",
+    "useDerived",
+    "useView",
+    "useEffect",
+    "useAsync",
+    "useAction",
+    "useSuspend",
+    "useRef",
+    "useRouter",
+    "useLookup",
+    "useMountEffect",
+    "useCleanup",
+    "useCatchError",
+];
+
+/// Functions exported by rask-ui, other than the setup hooks
+pub const RUNTIME_FUNCTIONS: &[&str] = &["render", "createContext", "assignState", "assignRef", "inspect"];
+
+/// The kind of reactive value a setup binding holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactiveKind {
+    Props,
+    State,
+    Derived,
+    View,
+    Async,
+    Router,
+    Context,
+}
+
+impl ReactiveKind {
+    /// Human readable description used in diagnostic messages
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ReactiveKind::Props => "props",
+            ReactiveKind::State => "useState state",
+            ReactiveKind::Derived => "useDerived value",
+            ReactiveKind::View => "useView value",
+            ReactiveKind::Async => "useAsync value",
+            ReactiveKind::Router => "useRouter router",
+            ReactiveKind::Context => "context value",
+        }
+    }
+}
+
+/// Bindings in a component's setup scope that hold reactive values
+#[derive(Debug, Default)]
+pub struct ReactiveBindings {
+    bindings: HashMap<Id, ReactiveKind>,
+}
+
+impl ReactiveBindings {
+    /// Collect the props parameter and every top-level setup declaration initialized by a reactive hook
+    pub fn collect(func: &Function) -> Self {
+        let mut bindings = HashMap::new();
+
+        if let Some(Pat::Ident(props)) = func.params.first().map(|param| &param.pat) {
+            bindings.insert(props.id.to_id(), ReactiveKind::Props);
+        }
+
+        for stmt in setup_stmts(func) {
+            if let Stmt::Decl(Decl::Var(var_decl)) = stmt {
+                for decl in &var_decl.decls {
+                    if let (Pat::Ident(binding), Some(init)) = (&decl.name, &decl.init) {
+                        if let Some(kind) = reactive_kind_of_init(init) {
+                            bindings.insert(binding.id.to_id(), kind);
+                        }
+                    }
+                }
+            }
+        }
+
+        ReactiveBindings { bindings }
+    }
+
+    pub fn get(&self, ident: &Ident) -> Option<ReactiveKind> {
+        self.bindings.get(&ident.to_id()).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+/// Strip parentheses and TypeScript-only wrappers from an expression
+pub fn unwrap_expr(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_expr(&paren.expr),
+        Expr::TsAs(ts_as) => unwrap_expr(&ts_as.expr),
+        Expr::TsNonNull(non_null) => unwrap_expr(&non_null.expr),
+        Expr::TsSatisfies(satisfies) => unwrap_expr(&satisfies.expr),
+        Expr::TsTypeAssertion(assertion) => unwrap_expr(&assertion.expr),
+        Expr::TsConstAssertion(assertion) => unwrap_expr(&assertion.expr),
+        _ => expr,
+    }
+}
+
+/// Name of the function being called when the callee is a plain identifier, e.g. `useState(...)`
+pub fn callee_name(call: &CallExpr) -> Option<&str> {
+    if let Callee::Expr(callee) = &call.callee {
+        if let Expr::Ident(ident) = unwrap_expr(callee) {
+            return Some(ident.sym.as_ref());
+        }
+    }
+    None
+}
+
+/// Method name and receiver when the callee is a member expression, e.g. `state.items.push(...)`
+pub fn callee_method(call: &CallExpr) -> Option<(&Expr, &str)> {
+    if let Callee::Expr(callee) = &call.callee {
+        if let Expr::Member(member) = unwrap_expr(callee) {
+            if let MemberProp::Ident(prop) = &member.prop {
+                return Some((&member.obj, prop.sym.as_ref()));
+            }
+        }
+    }
+    None
+}
+
+/// Determine whether a setup initializer produces a reactive value
+pub fn reactive_kind_of_init(init: &Expr) -> Option<ReactiveKind> {
+    let Expr::Call(call) = unwrap_expr(init) else {
+        return None;
+    };
+
+    match callee_name(call) {
+        Some("useState") => return Some(ReactiveKind::State),
+        Some("useDerived") => return Some(ReactiveKind::Derived),
+        Some("useView") => return Some(ReactiveKind::View),
+        Some("useAsync") => return Some(ReactiveKind::Async),
+        Some("useRouter") => return Some(ReactiveKind::Router),
+        _ => {}
+    }
+
+    // Context consumption and injection: `ThemeContext.use()` / `ThemeContext.inject()`
+    if let Some((obj, method)) = callee_method(call) {
+        if matches!(unwrap_expr(obj), Expr::Ident(_)) && (method == "use" || method == "inject") {
+            return Some(ReactiveKind::Context);
+        }
+    }
+
+    None
+}
+
+/// Name of the hook when the call is a setup hook or a context `use()`/`inject()` call
+pub fn hook_call_name(call: &CallExpr) -> Option<String> {
+    if let Some(name) = callee_name(call) {
+        return SETUP_HOOKS.contains(&name).then(|| name.to_string());
+    }
+    if let Some((obj, method)) = callee_method(call) {
+        if let Expr::Ident(context) = unwrap_expr(obj) {
+            if method == "use" || method == "inject" {
+                return Some(format!("{}.{}", context.sym, method));
+            }
+        }
+    }
+    None
+}
+
+/// Check if a statement only calls a hook, e.g. `const state = useState(...)` or `useEffect(...)`
+pub fn is_hook_stmt(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Decl(Decl::Var(var_decl)) => var_decl.decls.iter().all(|decl| {
+            matches!(decl.init.as_deref().map(unwrap_expr), Some(Expr::Call(call)) if hook_call_name(call).is_some())
+        }),
+        Stmt::Expr(expr_stmt) => {
+            matches!(unwrap_expr(&expr_stmt.expr), Expr::Call(call) if hook_call_name(call).is_some())
+        }
+        _ => false,
+    }
+}
+
+/// Find the identifier at the root of a member chain, e.g. `state` in `state.user.name`
+pub fn member_root(expr: &Expr) -> Option<&Ident> {
+    match unwrap_expr(expr) {
+        Expr::Ident(ident) => Some(ident),
+        Expr::Member(member) => member_root(&member.obj),
+        Expr::OptChain(opt_chain) => match &*opt_chain.base {
+            OptChainBase::Member(member) => member_root(&member.obj),
+            OptChainBase::Call(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Arrow functions returned from the top level of a stateful component's setup
+pub fn render_closures(func: &Function) -> Vec<&ArrowExpr> {
+    let mut closures = vec![];
+    if let Some(body) = &func.body {
+        for stmt in &body.stmts {
+            if let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = stmt {
+                if let Expr::Arrow(arrow) = &**arg {
+                    closures.push(arrow);
+                }
+            }
+        }
+    }
+    closures
+}
+
+/// Statements of a stateful component's setup body, excluding the returned render closure
+pub fn setup_stmts(func: &Function) -> impl Iterator<Item = &Stmt> {
+    func.body.iter().flat_map(|body| body.stmts.iter()).filter(|stmt| {
+        !matches!(
+            stmt,
+            Stmt::Return(ReturnStmt { arg: Some(arg), .. }) if matches!(&**arg, Expr::Arrow(_))
+        )
+    })
+}
@@ -0,0 +1,117 @@
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_name, SETUP_HOOKS};
+use crate::diagnostics::Diagnostic;
+
+pub const RULE: &str = "hook-after-await";
+
+/// Flag hooks called in an async setup after its first `await`, when the component is no longer being set up
+pub fn check(func: &Function, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(body) = &func.body else {
+        return;
+    };
+
+    // Visited in evaluation order, so `useState(await load())` is found as well as later statements
+    let mut finder = HookFinder {
+        awaited: false,
+        diagnostics,
+    };
+    body.visit_with(&mut finder);
+}
+
+/// Wrap the body of an async setup in `useAsyncSetup(async () => { ... })`, which renders a fallback
+/// until the setup resolves to the render function
+pub fn wrap_setup(func: &mut Function, use_async_setup: &Ident) {
+    let Some(body) = func.body.take() else {
+        return;
+    };
+    let span = body.span;
+
+    // An arrow keeps the `this` and `arguments` of the component function
+    let setup = Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params: vec![],
+        body: Box::new(BlockStmtOrExpr::BlockStmt(body)),
+        is_async: true,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    });
+
+    func.body = Some(BlockStmt {
+        span,
+        ctxt: Default::default(),
+        stmts: vec![Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::Call(CallExpr {
+                span: DUMMY_SP,
+                ctxt: Default::default(),
+                callee: Callee::Expr(Box::new(Expr::Ident(use_async_setup.clone()))),
+                args: vec![ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(setup),
+                }],
+                type_args: None,
+            }))),
+        })],
+    });
+    func.is_async = false;
+    func.return_type = None;
+}
+
+struct HookFinder<'a> {
+    /// An `await` has been evaluated before the current expression
+    awaited: bool,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Visit for HookFinder<'_> {
+    noop_visit_type!();
+
+    // The render function and callbacks are checked by the other hook rules
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_await_expr(&mut self, expr: &AwaitExpr) {
+        expr.visit_children_with(self);
+        self.awaited = true;
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        stmt.left.visit_with(self);
+        stmt.right.visit_with(self);
+        self.awaited |= stmt.is_await;
+        stmt.body.visit_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        // Arguments are evaluated before the call, `useState(await load())` runs after the `await`
+        call.visit_children_with(self);
+
+        if !self.awaited {
+            return;
+        }
+        let Some(name) = callee_name(call) else {
+            return;
+        };
+        if !SETUP_HOOKS.contains(&name) {
+            return;
+        }
+        self.diagnostics.push(
+            Diagnostic::error(
+                RULE,
+                call.span,
+                format!(
+                    "`{}` is called after `await`, when the async setup has already returned and the component is no longer being set up",
+                    name
+                ),
+            )
+            .with_suggestion(format!("Call `{}` before the first `await` of the setup", name)),
+        );
+    }
+}
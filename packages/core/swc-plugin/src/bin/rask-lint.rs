@@ -0,0 +1,616 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use swc_core::common::{
+    errors::{ColorConfig, Handler, HANDLER},
+    sync::Lrc,
+    SourceMap,
+};
+use swc_plugin_rask_component::{
+    codemod::{
+        convert::{self, Direction},
+        migrations, react, CodemodResult,
+    },
+    diagnostics::Severity,
+    fix::apply_fixes,
+    lint::{collect_files, lint_file, LintedFile},
+    manifest::{ProjectManifest, ProjectRoute, RouteManifestFile, MANIFEST_VERSION},
+    report, routes, Config,
+};
+
+const USAGE: &str = "Usage: rask-lint [options] <file or directory>...
+       rask-lint explain [--config <json>] [--format pretty|json] <file or directory>...
+       rask-lint routes [--config <json>] [--manifest <file>] <file or directory>...
+       rask-lint convert --to stateful|stateless [--dry-run] <file[:line] or directory>...
+       rask-lint migrate react [--dry-run] <file or directory>...
+       rask-lint migrate rask [--from <version>] [--config <json>] [--dry-run] <file or directory>...
+
+Checks .tsx/.jsx files against the Rask component rules.
+The explain command reports why each function was or wasn't compiled into a component.
+The routes command validates the `useRouter` configs and prints the project's routes, with
+their params and owning components, as JSON.
+The convert command rewrites components between the stateless and stateful forms,
+every component of a file or only the one declared at the given line.
+The migrate command rewrites code written for other frameworks or older releases, `react`
+converts React function components and `rask` upgrades rask-ui APIs changed after the
+given version, reporting what could not be converted.
+
+Options:
+  --config <json>    Plugin configuration, the same JSON object given to the SWC plugin
+  --format <format>  Output format: pretty (default), json or sarif
+  --fix              Apply the available fixes and write them back to the files
+  --fix-dry-run      Print the available fixes as a diff without writing them
+                     to stderr with --format json|sarif, so the report stays valid
+  --manifest <file>  Write a manifest of every component in the checked files,
+                     or of every route with the routes command
+  --to <kind>        convert: the kind of component to convert to
+  --from <version>   migrate rask: the rask-ui version the code was written for
+  --dry-run          convert, migrate: print the changes as a diff without writing them
+  -h, --help         Print this message";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Pretty,
+    Json,
+    Sarif,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Lint,
+    Explain,
+    Routes,
+    Convert(Direction),
+    Migrate,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FixMode {
+    Off,
+    Write,
+    DryRun,
+}
+
+struct Args {
+    command: Command,
+    /// Name of the migration to run with the migrate command
+    migration: Option<String>,
+    /// Version the code was written for, with `migrate rask`
+    from_version: Option<String>,
+    config: Config,
+    format: Format,
+    fix: FixMode,
+    manifest: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut config = Config::default();
+    let mut format = Format::Pretty;
+    let mut fix = FixMode::Off;
+    let mut manifest = None;
+    let mut paths = vec![];
+    let mut args = std::env::args().skip(1).peekable();
+
+    let mut command = match args.peek().map(String::as_str) {
+        Some("explain") => Command::Explain,
+        Some("routes") => Command::Routes,
+        Some("convert") => Command::Convert(Direction::Stateful),
+        Some("migrate") => Command::Migrate,
+        _ => Command::Lint,
+    };
+    if command != Command::Lint {
+        args.next();
+    }
+    let migration = if command == Command::Migrate {
+        Some(args.next().ok_or_else(|| format!("migrate expects the name of a migration\n\n{}", USAGE))?)
+    } else {
+        None
+    };
+    let mut direction = None;
+    let mut from_version = None;
+    // Options given, checked against the options of the command once it is known
+    let mut options: Vec<String> = vec![];
+
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            options.push(arg.clone());
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            "--config" => {
+                let json = args.next().ok_or("--config expects a JSON object")?;
+                config = serde_json::from_str(&json).map_err(|err| format!("Invalid --config: {}", err))?;
+            }
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("pretty") => Format::Pretty,
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    _ => return Err("--format expects one of pretty, json or sarif".to_string()),
+                };
+            }
+            "--fix" => fix = FixMode::Write,
+            "--fix-dry-run" => fix = FixMode::DryRun,
+            "--manifest" => manifest = Some(PathBuf::from(args.next().ok_or("--manifest expects a file path")?)),
+            "--to" => {
+                direction = Some(
+                    args.next()
+                        .as_deref()
+                        .and_then(Direction::parse)
+                        .ok_or("--to expects stateful or stateless")?,
+                );
+            }
+            "--dry-run" => fix = FixMode::DryRun,
+            "--from" => from_version = Some(args.next().ok_or("--from expects a rask-ui version")?),
+            flag if flag.starts_with('-') => return Err(format!("Unknown option {}\n\n{}", flag, USAGE)),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    if command == Command::Migrate && !matches!(migration.as_deref(), Some("react") | Some("rask")) {
+        return Err(format!("Unknown migration {}\n\n{}", migration.unwrap_or_default(), USAGE));
+    }
+    // `lint --dry-run` would otherwise run as `--fix-dry-run`, and `migrate react --from` be ignored
+    let (name, supported) = command_options(command, migration.as_deref());
+    if let Some(option) = options.iter().find(|option| !supported.contains(&option.as_str())) {
+        return Err(format!("{} is not an option of {}\n\n{}", option, name, USAGE));
+    }
+    match command {
+        Command::Explain if format == Format::Sarif => {
+            return Err(format!("explain only supports --format pretty|json\n\n{}", USAGE));
+        }
+        Command::Convert(_) => {
+            let direction = direction.ok_or_else(|| format!("convert expects --to stateful|stateless\n\n{}", USAGE))?;
+            command = Command::Convert(direction);
+            if fix == FixMode::Off {
+                fix = FixMode::Write;
+            }
+        }
+        Command::Migrate => {
+            if fix == FixMode::Off {
+                fix = FixMode::Write;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(Args {
+        command,
+        migration,
+        from_version,
+        config,
+        format,
+        fix,
+        manifest,
+        paths,
+    })
+}
+
+/// The name of a command, for errors, and the options it supports
+fn command_options(command: Command, migration: Option<&str>) -> (&'static str, &'static [&'static str]) {
+    match (command, migration) {
+        (Command::Lint, _) => ("lint", &["--config", "--format", "--fix", "--fix-dry-run", "--manifest"]),
+        (Command::Explain, _) => ("explain", &["--config", "--format"]),
+        (Command::Routes, _) => ("routes", &["--config", "--manifest"]),
+        (Command::Convert(_), _) => ("convert", &["--to", "--dry-run"]),
+        (Command::Migrate, Some("rask")) => ("migrate rask", &["--from", "--config", "--dry-run"]),
+        (Command::Migrate, _) => ("migrate react", &["--dry-run"]),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Command::Convert(direction) = args.command {
+        return convert_files(&args, direction);
+    }
+    if args.command == Command::Migrate {
+        return migrate_files(&args);
+    }
+
+    let files = match collect_files(&args.paths) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("rask-lint: {}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    let cm: Lrc<SourceMap> = Default::default();
+
+    if args.command == Command::Explain {
+        return explain(&cm, &files, &args);
+    }
+    if args.command == Command::Routes {
+        return export_routes(&cm, &files, &args);
+    }
+
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+
+    let mut linted_files = vec![];
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut fixed = 0;
+
+    for path in &files {
+        let mut linted = match lint_file(&cm, path, &args.config) {
+            Ok(linted) => linted,
+            Err(err) => {
+                eprintln!("rask-lint: {}: {}", path.display(), err);
+                errors += 1;
+                continue;
+            }
+        };
+
+        if args.fix != FixMode::Off {
+            match fix_file(&mut linted, args.fix, args.format) {
+                Ok(count) => fixed += count,
+                Err(err) => {
+                    eprintln!("rask-lint: {}: {}", path.display(), err);
+                    errors += 1;
+                }
+            }
+        }
+
+        for diagnostic in &linted.diagnostics {
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+        }
+
+        if args.format == Format::Pretty {
+            HANDLER.set(&handler, || {
+                for diagnostic in &linted.diagnostics {
+                    diagnostic.emit();
+                }
+            });
+        }
+
+        linted_files.push(linted);
+    }
+
+    match args.format {
+        Format::Pretty => {}
+        Format::Json => println!("{}", report::to_json(&cm, &linted_files)),
+        Format::Sarif => println!("{}", report::to_sarif(&cm, &linted_files)),
+    }
+
+    if let Some(manifest_path) = &args.manifest {
+        let manifest = ProjectManifest {
+            version: MANIFEST_VERSION,
+            modules: linted_files.iter().map(|linted| linted.manifest.clone()).collect(),
+        };
+        let json = serde_json::to_string_pretty(&manifest).expect("manifest serializes");
+        if let Err(err) = fs::write(manifest_path, json) {
+            eprintln!("rask-lint: {}: {}", manifest_path.display(), err);
+            errors += 1;
+        }
+    }
+
+    match args.fix {
+        FixMode::Off => {}
+        FixMode::Write => eprintln!("rask-lint: fixed {} issue(s)", fixed),
+        FixMode::DryRun => eprintln!("rask-lint: {} issue(s) can be fixed with --fix", fixed),
+    }
+
+    eprintln!(
+        "rask-lint: checked {} file(s), {} error(s), {} warning(s)",
+        files.len(),
+        errors,
+        warnings
+    );
+
+    if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Print the classification decision of every candidate function in the given files
+fn explain(cm: &Lrc<SourceMap>, files: &[PathBuf], args: &Args) -> ExitCode {
+    let mut failed = false;
+    let mut entries = vec![];
+
+    for path in files {
+        let linted = match lint_file(cm, path, &args.config) {
+            Ok(linted) => linted,
+            Err(err) => {
+                eprintln!("rask-lint: {}: {}", path.display(), err);
+                failed = true;
+                continue;
+            }
+        };
+
+        for explanation in linted.explanations {
+            if args.format == Format::Pretty {
+                let (line, column) = explanation
+                    .location
+                    .as_ref()
+                    .map(|location| (location.start.line, location.start.column))
+                    .unwrap_or((0, 0));
+                println!(
+                    "{}:{}:{} {} {}: {}",
+                    path.display(),
+                    line,
+                    column,
+                    explanation.name,
+                    explanation.decision.as_str(),
+                    explanation.reason
+                );
+            } else {
+                entries.push(serde_json::json!({
+                    "file": path.display().to_string(),
+                    "name": explanation.name,
+                    "decision": explanation.decision,
+                    "reason": explanation.reason,
+                    "location": explanation.location,
+                }));
+            }
+        }
+    }
+
+    if args.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&entries).expect("explanations serialize"));
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Report the route diagnostics of the given files and export every route they declare
+fn export_routes(cm: &Lrc<SourceMap>, files: &[PathBuf], args: &Args) -> ExitCode {
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+    let route_rules = [routes::DUPLICATE_RULE, routes::INVALID_RULE, routes::CONFLICT_RULE];
+    let mut failed = false;
+    let mut project_routes = vec![];
+
+    for path in files {
+        let linted = match lint_file(cm, path, &args.config) {
+            Ok(linted) => linted,
+            Err(err) => {
+                eprintln!("rask-lint: {}: {}", path.display(), err);
+                failed = true;
+                continue;
+            }
+        };
+
+        HANDLER.set(&handler, || {
+            for diagnostic in &linted.diagnostics {
+                if route_rules.contains(&diagnostic.rule) {
+                    failed |= diagnostic.severity == Severity::Error;
+                    diagnostic.emit();
+                }
+            }
+        });
+
+        for route in linted.manifest.routes {
+            project_routes.push(ProjectRoute {
+                file: linted.manifest.file.clone(),
+                route,
+            });
+        }
+    }
+
+    let manifest = RouteManifestFile {
+        version: MANIFEST_VERSION,
+        routes: project_routes,
+    };
+    let json = serde_json::to_string_pretty(&manifest).expect("routes serialize");
+    match &args.manifest {
+        Some(manifest_path) => {
+            if let Err(err) = fs::write(manifest_path, json) {
+                eprintln!("rask-lint: {}: {}", manifest_path.display(), err);
+                failed = true;
+            }
+        }
+        None => println!("{}", json),
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Convert the components of the given files, a `file:line` target converts only the component at that line
+fn convert_files(args: &Args, direction: Direction) -> ExitCode {
+    let cm: Lrc<SourceMap> = Default::default();
+    let mut failed = false;
+    let mut converted = 0;
+
+    for target in &args.paths {
+        let (path, line) = split_line_target(target);
+        let files = if line.is_none() && path.is_dir() {
+            match collect_files(&[path]) {
+                Ok(files) => files,
+                Err(err) => {
+                    eprintln!("rask-lint: {}: {}", target.display(), err);
+                    failed = true;
+                    continue;
+                }
+            }
+        } else {
+            vec![path]
+        };
+
+        for file in files {
+            match convert::run(&cm, &file, direction, line).and_then(|result| write_codemod(&result, args.fix)) {
+                Ok(count) => converted += count,
+                Err(err) => {
+                    eprintln!("rask-lint: {}: {}", file.display(), err);
+                    failed = true;
+                }
+            }
+        }
+    }
+
+    eprintln!("rask-lint: converted {} component(s)", converted);
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Run the migration named on the command line over the given files
+fn migrate_files(args: &Args) -> ExitCode {
+    let files = match collect_files(&args.paths) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("rask-lint: {}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    let pending = if args.migration.as_deref() == Some("rask") {
+        match migrations::pending(args.from_version.as_deref()) {
+            Ok(pending) => pending,
+            Err(err) => {
+                eprintln!("rask-lint: {}", err);
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        vec![]
+    };
+    for migration in &pending {
+        eprintln!("rask-lint: applying {}: {}", migration.version, migration.description);
+    }
+    let import_source = args.config.import_source.as_deref().unwrap_or("rask-ui");
+
+    let cm: Lrc<SourceMap> = Default::default();
+    let mut failed = false;
+    let mut migrated = 0;
+    let mut todos = 0;
+
+    for file in &files {
+        let result = match args.migration.as_deref() {
+            Some("rask") => migrations::run(&cm, file, &pending, import_source),
+            _ => react::run(&cm, file),
+        };
+        match result.and_then(|result| {
+            todos += result.skipped.len();
+            write_codemod(&result, args.fix)
+        }) {
+            Ok(count) => migrated += count,
+            Err(err) => {
+                eprintln!("rask-lint: {}: {}", file.display(), err);
+                failed = true;
+            }
+        }
+    }
+
+    eprintln!(
+        "rask-lint: applied {} change(s), {} item(s) left to convert by hand",
+        migrated, todos
+    );
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Split `file.tsx:12` into the file and line, paths without a numeric suffix are kept whole
+fn split_line_target(target: &Path) -> (PathBuf, Option<usize>) {
+    let text = target.to_string_lossy();
+    if let Some((path, line)) = text.rsplit_once(':') {
+        if let Ok(line) = line.parse() {
+            return (PathBuf::from(path), Some(line));
+        }
+    }
+    (target.to_path_buf(), None)
+}
+
+/// Report what a codemod did to a file, then write the output or print it as a diff
+fn write_codemod(result: &CodemodResult, mode: FixMode) -> std::io::Result<usize> {
+    let path = result.path.display().to_string();
+    for applied in &result.applied {
+        eprintln!("{}: {}", path, applied);
+    }
+    for skipped in &result.skipped {
+        eprintln!("{}: skipped {}", path, skipped);
+    }
+    if !result.changed() {
+        return Ok(0);
+    }
+
+    match mode {
+        FixMode::DryRun => {
+            let diff = similar::TextDiff::from_lines(&result.source, &result.output);
+            print!(
+                "{}",
+                diff.unified_diff()
+                    .context_radius(3)
+                    .header(&format!("a/{}", path), &format!("b/{}", path))
+            );
+        }
+        _ => fs::write(&result.path, &result.output)?,
+    }
+
+    Ok(result.applied.len())
+}
+
+/// Apply the fixes of a linted file, either writing the file or printing a diff.
+/// Fixed diagnostics are removed when the file is written, so only the remaining issues are reported.
+/// The diff goes to stderr with `--format json|sarif`, which own stdout.
+fn fix_file(linted: &mut LintedFile, mode: FixMode, format: Format) -> std::io::Result<usize> {
+    let source = linted.source.src.as_str();
+    let (fixed_source, applied) = apply_fixes(source, linted.source.start_pos, &linted.diagnostics);
+    if applied.is_empty() {
+        return Ok(0);
+    }
+
+    let path = linted.path.display().to_string();
+    for &index in &applied {
+        if let Some(fix) = &linted.diagnostics[index].fix {
+            eprintln!("{}: {} [{}]", path, fix.description, linted.diagnostics[index].rule);
+        }
+    }
+
+    match mode {
+        FixMode::Write => {
+            fs::write(&linted.path, fixed_source)?;
+            let mut index = 0;
+            linted.diagnostics.retain(|_| {
+                let keep = !applied.contains(&index);
+                index += 1;
+                keep
+            });
+        }
+        FixMode::DryRun => {
+            let diff = similar::TextDiff::from_lines(source, &fixed_source);
+            let diff = diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&format!("a/{}", path), &format!("b/{}", path))
+                .to_string();
+            match format {
+                Format::Pretty => print!("{}", diff),
+                Format::Json | Format::Sarif => eprint!("{}", diff),
+            }
+        }
+        FixMode::Off => {}
+    }
+
+    Ok(applied.len())
+}
@@ -0,0 +1,308 @@
+use std::{collections::HashMap, error::Error, path::PathBuf, process::ExitCode};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Request as _},
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    InitializeParams, NumberOrString, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+use swc_core::common::{sync::Lrc, BytePos, SourceMap, Span};
+use swc_plugin_rask_component::{
+    compile::{parse_source, syntax_for},
+    diagnostics::{Diagnostic, Fix, Severity},
+    lint::lint_source,
+    manifest::ComponentKind,
+    refactor::{component_at, move_read_into_render, to_stateful, to_stateless, SourceText},
+    Config,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Open documents and the plugin configuration given as initialization options
+struct Server {
+    config: Config,
+    documents: HashMap<Url, String>,
+}
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    if let Err(err) = run(connection) {
+        eprintln!("rask-lsp: {}", err);
+        return ExitCode::FAILURE;
+    }
+    match io_threads.join() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("rask-lsp: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(connection: Connection) -> Result<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+    let params: InitializeParams = serde_json::from_value(connection.initialize(serde_json::to_value(capabilities)?)?)?;
+
+    // The initialization options are the same JSON object given to the SWC plugin
+    let config = params
+        .initialization_options
+        .and_then(|options| serde_json::from_value(options).ok())
+        .unwrap_or_default();
+    let mut server = Server {
+        config,
+        documents: HashMap::new(),
+    };
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(publish) = server.handle_notification(notification)? {
+                    connection.sender.send(Message::Notification(publish))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+impl Server {
+    /// Track document contents, returning the diagnostics to publish for the changed document
+    fn handle_notification(&mut self, notification: Notification) -> Result<Option<Notification>> {
+        let (uri, diagnostics) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), params.text_document.text);
+                let diagnostics = self.diagnostics(&uri);
+                (uri, diagnostics)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                // Documents are synced in full, so the last change holds the whole text
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(uri.clone(), change.text);
+                }
+                let diagnostics = self.diagnostics(&uri);
+                (uri, diagnostics)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                (params.text_document.uri, vec![])
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        )))
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        if request.method != CodeActionRequest::METHOD {
+            return Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unhandled method {}", request.method),
+            );
+        }
+        match serde_json::from_value::<CodeActionParams>(request.params) {
+            Ok(params) => Response::new_ok(request.id, self.code_actions(&params)),
+            Err(err) => Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string()),
+        }
+    }
+
+    fn diagnostics(&self, uri: &Url) -> Vec<lsp_types::Diagnostic> {
+        let Some(text) = self.documents.get(uri) else {
+            return vec![];
+        };
+        let cm: Lrc<SourceMap> = Default::default();
+        let linted = lint_source(&cm, &document_path(uri), text.clone(), &self.config);
+        let start_pos = linted.source.start_pos;
+
+        linted
+            .diagnostics
+            .iter()
+            .map(|diagnostic| lsp_diagnostic(text, start_pos, diagnostic))
+            .collect()
+    }
+
+    /// Quick fixes for the diagnostics in the range, and refactors of the component at its start
+    fn code_actions(&self, params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let Some(text) = self.documents.get(uri) else {
+            return vec![];
+        };
+        let path = document_path(uri);
+        let cm: Lrc<SourceMap> = Default::default();
+        let linted = lint_source(&cm, &path, text.clone(), &self.config);
+        let start_pos = linted.source.start_pos;
+        let source = SourceText { text, start_pos };
+
+        let mut actions = vec![];
+        for diagnostic in &linted.diagnostics {
+            let Some(fix) = &diagnostic.fix else {
+                continue;
+            };
+            let range = lsp_range(text, start_pos, diagnostic.span);
+            if range.start <= params.range.end && params.range.start <= range.end {
+                actions.push(code_action(
+                    uri,
+                    text,
+                    start_pos,
+                    fix,
+                    CodeActionKind::QUICKFIX,
+                    vec![lsp_diagnostic(text, start_pos, diagnostic)],
+                ));
+            }
+        }
+
+        // Refactors work on the untransformed module, which the lint run doesn't hand out
+        let mut parse_errors = vec![];
+        let Some(module) = parse_source(&linted.source, syntax_for(&path), None, &mut parse_errors) else {
+            return actions;
+        };
+        let pos = start_pos + BytePos(offset_at(text, params.range.start) as u32);
+
+        if let Some(component) = component_at(&module, pos) {
+            let refactors = match component.kind {
+                ComponentKind::Stateless => vec![to_stateful(&component.func, source).ok()],
+                ComponentKind::Stateful => vec![
+                    to_stateless(&component.func, source).ok(),
+                    move_read_into_render(&component.func, source, pos),
+                ],
+            };
+            for fix in refactors.into_iter().flatten() {
+                actions.push(code_action(
+                    uri,
+                    text,
+                    start_pos,
+                    &fix,
+                    CodeActionKind::REFACTOR_REWRITE,
+                    vec![],
+                ));
+            }
+        }
+
+        actions
+    }
+}
+
+fn document_path(uri: &Url) -> PathBuf {
+    uri.to_file_path().unwrap_or_else(|_| PathBuf::from(uri.path()))
+}
+
+fn code_action(
+    uri: &Url,
+    text: &str,
+    start_pos: BytePos,
+    fix: &Fix,
+    kind: CodeActionKind,
+    diagnostics: Vec<lsp_types::Diagnostic>,
+) -> CodeActionOrCommand {
+    let edits = fix
+        .edits
+        .iter()
+        .map(|edit| TextEdit {
+            range: lsp_range(text, start_pos, edit.span),
+            new_text: edit.replacement.clone(),
+        })
+        .collect();
+
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title: fix.description.clone(),
+        kind: Some(kind),
+        diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+fn lsp_diagnostic(text: &str, start_pos: BytePos, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+    let message = match &diagnostic.suggestion {
+        Some(suggestion) => format!("{}\n{}", diagnostic.message, suggestion),
+        None => diagnostic.message.clone(),
+    };
+
+    lsp_types::Diagnostic {
+        range: lsp_range(text, start_pos, diagnostic.span),
+        severity: Some(match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        code: Some(NumberOrString::String(diagnostic.rule.to_string())),
+        source: Some("rask".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+fn lsp_range(text: &str, start_pos: BytePos, span: Span) -> Range {
+    let offset = |pos: BytePos| pos.0.saturating_sub(start_pos.0) as usize;
+    Range {
+        start: position_at(text, offset(span.lo)),
+        end: position_at(text, offset(span.hi)),
+    }
+}
+
+/// LSP position of a byte offset, with the character counted in UTF-16 code units
+fn position_at(text: &str, offset: usize) -> Position {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Byte offset of an LSP position, clamped to the end of its line
+fn offset_at(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+    for (index, char) in text[line_start..].char_indices() {
+        if units >= position.character as usize || char == '\n' {
+            return line_start + index;
+        }
+        units += char.len_utf16();
+    }
+    text.len()
+}
@@ -0,0 +1,294 @@
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::private_ident,
+    visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use crate::ssr::{arg, factory_name, is_empty};
+
+/// `VNodeFlags.SvgElement`, whose `className` is not a string property
+const SVG_ELEMENT_FLAG: f64 = 32.0;
+
+/// `ChildFlags.UnknownChildren`, so Inferno normalizes children that became bindings
+const UNKNOWN_CHILDREN: f64 = 0.0;
+
+/// `ChildFlags.HasInvalidChildren`, the flags of an element without children
+const INVALID_CHILDREN: f64 = 1.0;
+
+/// Props Inferno handles itself, which are never bound
+const UNBOUND_PROPS: &[&str] = &["key", "ref", "children", "style", "dangerouslySetInnerHTML"];
+
+/// The runtime helpers used by the compiled bindings, imported once per module
+#[derive(Default)]
+pub struct Helpers {
+    pub bind_text: Option<Ident>,
+    pub bind_attrs: Option<Ident>,
+}
+
+/// Compile the reactive text children and attributes of a render closure into bindings.
+/// `{state.count}` becomes `bindText(() => state.count)` and `className={state.active ? "a" : "b"}`
+/// an attribute set by the `bindAttrs(...)` ref, so only the binding runs again when they change.
+pub fn compile_render(render: &mut ArrowExpr, helpers: &mut Helpers) {
+    render.body.visit_mut_with(&mut BindingCompiler { helpers });
+}
+
+struct BindingCompiler<'a> {
+    helpers: &'a mut Helpers,
+}
+
+impl BindingCompiler<'_> {
+    fn bind_text(&mut self, expr: Box<Expr>) -> Box<Expr> {
+        let bind_text = self
+            .helpers
+            .bind_text
+            .get_or_insert_with(|| private_ident!("bindText"))
+            .clone();
+        Box::new(call(bind_text, vec![read(expr)]))
+    }
+
+    /// Replace bindable children, returning whether any was replaced
+    fn bind_children(&mut self, children: &mut Expr) -> bool {
+        match children {
+            Expr::Array(array) => {
+                let mut bound = false;
+                for child in array.elems.iter_mut().flatten() {
+                    if child.spread.is_none() && is_bindable(&child.expr) {
+                        let expr =
+                            std::mem::replace(&mut child.expr, Box::new(Expr::Invalid(Invalid { span: DUMMY_SP })));
+                        child.expr = self.bind_text(expr);
+                        bound = true;
+                    }
+                }
+                bound
+            }
+            child if is_bindable(child) => {
+                let expr = std::mem::replace(child, Expr::Invalid(Invalid { span: DUMMY_SP }));
+                *child = *self.bind_text(Box::new(expr));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// `createVNode(flags, tag, className, children, childFlags, props, key, ref)`
+    fn bind_element(&mut self, call: &mut CallExpr) {
+        if call.args.iter().any(|arg| arg.spread.is_some()) {
+            return;
+        }
+
+        if call.args.len() > 3 && self.bind_children(&mut call.args[3].expr) {
+            if let Some(child_flags) = call.args.get_mut(4) {
+                child_flags.expr = Box::new(number(UNKNOWN_CHILDREN));
+            }
+        }
+
+        let mut attrs: Vec<(String, Box<Expr>)> = vec![];
+
+        let is_svg = matches!(&*call.args[0].expr, Expr::Lit(Lit::Num(flags)) if (flags.value as u32) & (SVG_ELEMENT_FLAG as u32) != 0);
+        if !is_svg && arg(call, 2).is_some_and(is_bindable) {
+            let class_name = std::mem::replace(&mut call.args[2].expr, Box::new(null()));
+            attrs.push(("className".to_string(), class_name));
+        }
+
+        if let Some(ExprOrSpread { expr, .. }) = call.args.get_mut(5) {
+            if let Expr::Object(props) = &mut **expr {
+                if props.props.iter().all(|prop| matches!(prop, PropOrSpread::Prop(_))) {
+                    let mut kept = vec![];
+                    for prop in std::mem::take(&mut props.props) {
+                        match bindable_prop(&prop) {
+                            Some(name) => {
+                                let PropOrSpread::Prop(prop) = prop else { unreachable!() };
+                                let Prop::KeyValue(KeyValueProp { value, .. }) = *prop else {
+                                    unreachable!()
+                                };
+                                attrs.push((name, value));
+                            }
+                            None => kept.push(prop),
+                        }
+                    }
+                    props.props = kept;
+                }
+            }
+        }
+
+        if attrs.is_empty() {
+            return;
+        }
+
+        let bind_attrs = self
+            .helpers
+            .bind_attrs
+            .get_or_insert_with(|| private_ident!("bindAttrs"))
+            .clone();
+        let reads = Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: attrs
+                .into_iter()
+                .map(|(name, value)| {
+                    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Str(Str {
+                            span: DUMMY_SP,
+                            value: Wtf8Atom::from(name.as_str()),
+                            raw: None,
+                        }),
+                        value: Box::new(read(value)),
+                    })))
+                })
+                .collect(),
+        });
+
+        // Pad the arguments up to the ref, the defaults match what Inferno assumes for missing ones
+        while call.args.len() < 7 {
+            let default = match call.args.len() {
+                4 => number(INVALID_CHILDREN),
+                _ => null(),
+            };
+            call.args.push(ExprOrSpread {
+                spread: None,
+                expr: Box::new(default),
+            });
+        }
+        let mut bind_args = vec![reads];
+        if let Some(ref_arg) = call.args.get(7).filter(|ref_arg| !is_empty(&ref_arg.expr)) {
+            bind_args.push((*ref_arg.expr).clone());
+        }
+        let ref_expr = Box::new(call(bind_attrs, bind_args));
+        match call.args.get_mut(7) {
+            Some(ref_arg) => ref_arg.expr = ref_expr,
+            None => call.args.push(ExprOrSpread {
+                spread: None,
+                expr: ref_expr,
+            }),
+        }
+    }
+}
+
+impl VisitMut for BindingCompiler<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.visit_mut_children_with(self);
+
+        match factory_name(call) {
+            Some("createVNode") if !call.args.is_empty() => self.bind_element(call),
+            // `createFragment(children, childFlags, key)`
+            Some("createFragment") if !call.args.is_empty() && call.args[0].spread.is_none() => {
+                if self.bind_children(&mut call.args[0].expr) {
+                    if let Some(child_flags) = call.args.get_mut(1) {
+                        child_flags.expr = Box::new(number(UNKNOWN_CHILDREN));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The attribute name of a prop whose value should be bound
+fn bindable_prop(prop: &PropOrSpread) -> Option<String> {
+    let PropOrSpread::Prop(prop) = prop else {
+        return None;
+    };
+    let Prop::KeyValue(KeyValueProp { key, value }) = &**prop else {
+        return None;
+    };
+    let name = match key {
+        PropName::Ident(ident) => ident.sym.to_string(),
+        PropName::Str(str) => str.value.to_string_lossy().into_owned(),
+        _ => return None,
+    };
+    let is_event = name.len() > 2 && name.starts_with("on") && name[2..].starts_with(|c: char| c.is_ascii_uppercase());
+    if is_event || name.starts_with('$') || UNBOUND_PROPS.contains(&name.as_str()) || !is_bindable(value) {
+        return None;
+    }
+    Some(name)
+}
+
+/// Whether an expression reads a reactive value and can only evaluate to text or an attribute value.
+/// Anything rendering VNodes is left to the component's render, plain literals and bindings never change.
+fn is_bindable(expr: &Expr) -> bool {
+    if matches!(expr, Expr::Lit(_) | Expr::Ident(_) | Expr::Arrow(_) | Expr::Fn(_)) || is_empty(expr) {
+        return false;
+    }
+    let mut finder = ReadFinder::default();
+    expr.visit_with(&mut finder);
+    finder.reads_member && !finder.renders
+}
+
+#[derive(Default)]
+struct ReadFinder {
+    reads_member: bool,
+    renders: bool,
+}
+
+impl Visit for ReadFinder {
+    noop_visit_type!();
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        self.renders |= factory_name(call).is_some();
+        call.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        self.reads_member = true;
+        member.visit_children_with(self);
+    }
+
+    fn visit_opt_chain_expr(&mut self, chain: &OptChainExpr) {
+        self.reads_member = true;
+        chain.visit_children_with(self);
+    }
+
+    // Untransformed JSX, as parsed by the native tools
+    fn visit_jsx_element(&mut self, _: &JSXElement) {
+        self.renders = true;
+    }
+
+    fn visit_jsx_fragment(&mut self, _: &JSXFragment) {
+        self.renders = true;
+    }
+}
+
+/// `() => <expr>`
+fn read(expr: Box<Expr>) -> Expr {
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params: vec![],
+        body: Box::new(BlockStmtOrExpr::Expr(expr)),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+fn call(callee: Ident, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(Box::new(Expr::Ident(callee))),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+    })
+}
+
+fn number(value: f64) -> Expr {
+    Expr::Lit(Lit::Num(Number {
+        span: DUMMY_SP,
+        value,
+        raw: None,
+    }))
+}
+
+fn null() -> Expr {
+    Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))
+}
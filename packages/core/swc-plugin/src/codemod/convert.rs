@@ -0,0 +1,116 @@
+use std::{io, path::Path};
+
+use swc_core::common::{sync::Lrc, SourceMap};
+
+use super::CodemodResult;
+use crate::compile::{parse_source, syntax_for};
+use crate::diagnostics::Fix;
+use crate::fix::apply_edits;
+use crate::manifest::ComponentKind;
+use crate::refactor::{find_components, to_stateful, to_stateless, SourceText};
+
+/// The kind of component to convert to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Stateful,
+    Stateless,
+}
+
+impl Direction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stateful" => Some(Direction::Stateful),
+            "stateless" => Some(Direction::Stateless),
+            _ => None,
+        }
+    }
+
+    fn target(self) -> ComponentKind {
+        match self {
+            Direction::Stateful => ComponentKind::Stateful,
+            Direction::Stateless => ComponentKind::Stateless,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Stateful => "stateful",
+            Direction::Stateless => "stateless",
+        }
+    }
+}
+
+/// Convert the components of a file, or only the innermost component around the given 1-based line
+pub fn run(cm: &Lrc<SourceMap>, path: &Path, direction: Direction, line: Option<usize>) -> io::Result<CodemodResult> {
+    let file = cm.load_file(path)?;
+    let mut errors = vec![];
+    let Some(module) = parse_source(&file, syntax_for(path), None, &mut errors) else {
+        let message = errors
+            .first()
+            .map(|error| error.message.clone())
+            .unwrap_or_else(|| "could not parse the file".to_string());
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    };
+
+    let mut components = find_components(&module);
+    if let Some(line) = line {
+        components.retain(|component| {
+            cm.lookup_char_pos(component.span.lo).line <= line && line <= cm.lookup_char_pos(component.span.hi).line
+        });
+        components = components
+            .into_iter()
+            .min_by_key(|component| component.span.hi.0 - component.span.lo.0)
+            .into_iter()
+            .collect();
+    }
+
+    let source = SourceText {
+        text: file.src.as_str(),
+        start_pos: file.start_pos,
+    };
+    let mut skipped = vec![];
+    let mut fixes: Vec<(String, Fix)> = vec![];
+
+    if let (Some(line), true) = (line, components.is_empty()) {
+        skipped.push(format!("no component is declared at line {}", line));
+    }
+
+    for component in &components {
+        if component.kind == direction.target() {
+            // Only worth reporting when the component was targeted explicitly
+            if line.is_some() {
+                skipped.push(format!("`{}` is already {}", component.name, direction.as_str()));
+            }
+            continue;
+        }
+
+        let fix = match direction {
+            Direction::Stateful => to_stateful(&component.func, source),
+            Direction::Stateless => to_stateless(&component.func, source),
+        };
+        match fix {
+            Ok(fix) => fixes.push((component.name.clone(), fix)),
+            Err(reason) => skipped.push(format!("`{}` {}", component.name, reason)),
+        }
+    }
+
+    let refs: Vec<Option<&Fix>> = fixes.iter().map(|(_, fix)| Some(fix)).collect();
+    let (output, accepted) = apply_edits(source.text, source.start_pos, &refs);
+
+    let mut applied = vec![];
+    for (index, (name, fix)) in fixes.iter().enumerate() {
+        if accepted.contains(&index) {
+            applied.push(format!("`{}`: {}", name, fix.description));
+        } else {
+            skipped.push(format!("`{}` overlaps another conversion, run the codemod again", name));
+        }
+    }
+
+    Ok(CodemodResult {
+        path: path.to_path_buf(),
+        source: source.text.to_string(),
+        output,
+        applied,
+        skipped,
+    })
+}
@@ -0,0 +1,300 @@
+use std::{collections::HashMap, io, path::Path};
+
+use swc_core::common::{sync::Lrc, SourceMap, Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use super::CodemodResult;
+use crate::compile::{parse_source, syntax_for};
+use crate::diagnostics::{Fix, TextEdit};
+use crate::fix::apply_edits;
+use crate::refactor::SourceText;
+
+/// A single API change of a rask-ui release
+pub enum Change {
+    /// An export was renamed, imports and references are rewritten
+    Rename { from: &'static str, to: &'static str },
+    /// An export was removed without a mechanical replacement, uses are reported
+    Removed { name: &'static str, hint: &'static str },
+}
+
+/// The API changes made in a rask-ui release
+pub struct Migration {
+    pub version: &'static str,
+    pub description: &'static str,
+    pub changes: &'static [Change],
+}
+
+/// Every migration, ordered by the release that made the changes.
+/// The repository keeps no changelog, so a migration names the first release whose exports are known to have
+/// the new API: the hook names are those of rask-ui 0.29.3, the old ones are documented in OBSERVER_SPEC.md.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: "0.29.3",
+    description: "Reactive primitives are hooks named `use*` (rask-ui 0.29.3, replacing the API of OBSERVER_SPEC.md)",
+    changes: &[
+        Change::Rename {
+            from: "createState",
+            to: "useState",
+        },
+        Change::Rename {
+            from: "createComputed",
+            to: "useDerived",
+        },
+        Change::Rename {
+            from: "createView",
+            to: "useView",
+        },
+        Change::Rename {
+            from: "createAsync",
+            to: "useAsync",
+        },
+        // `useAction` returns a `[state, run]` tuple instead of the mutation object, so uses can't be renamed
+        Change::Removed {
+            name: "createMutation",
+            hint: "use `const [state, run] = useAction(fn)`, read `state.isPending`/`state.error` and call `run(params)` instead of `mutate`",
+        },
+        Change::Removed {
+            name: "createQuery",
+            hint: "load the data with `useAsync` instead",
+        },
+    ],
+}];
+
+/// The migrations of releases after the given version, every migration without one
+pub fn pending(from: Option<&str>) -> Result<Vec<&'static Migration>, String> {
+    let from = from.map(parse_version).transpose()?;
+    let mut pending = vec![];
+    for migration in MIGRATIONS {
+        let version = parse_version(migration.version)?;
+        if from.map_or(true, |from| version > from) {
+            pending.push(migration);
+        }
+    }
+    Ok(pending)
+}
+
+fn parse_version(version: &str) -> Result<(u32, u32, u32), String> {
+    let mut parts = version.trim_start_matches('v').split('.').map(str::parse::<u32>);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), minor, patch) => Ok((
+            major,
+            minor.unwrap_or(Ok(0)).map_err(|_| format!("Invalid version {}", version))?,
+            patch.unwrap_or(Ok(0)).map_err(|_| format!("Invalid version {}", version))?,
+        )),
+        _ => Err(format!("Invalid version {}", version)),
+    }
+}
+
+/// Apply the changes of the given migrations to the rask-ui imports of a file and their uses
+pub fn run(
+    cm: &Lrc<SourceMap>,
+    path: &Path,
+    migrations: &[&Migration],
+    import_source: &str,
+) -> io::Result<CodemodResult> {
+    let file = cm.load_file(path)?;
+    let mut errors = vec![];
+    let Some(module) = parse_source(&file, syntax_for(path), None, &mut errors) else {
+        let message = errors
+            .first()
+            .map(|error| error.message.clone())
+            .unwrap_or_else(|| "could not parse the file".to_string());
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    };
+    let source = SourceText {
+        text: file.src.as_str(),
+        start_pos: file.start_pos,
+    };
+
+    let mut edits = vec![];
+    let mut applied = vec![];
+    let mut skipped = vec![];
+    // Local names of the changed exports with whether they are aliased, and the namespaces rask-ui is imported as
+    let mut locals: HashMap<String, (&'static Change, bool)> = HashMap::new();
+    let mut namespaces = vec![];
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        if &*import.src.value != import_source {
+            continue;
+        }
+        let imported_names: Vec<String> = import
+            .specifiers
+            .iter()
+            .filter_map(|specifier| match specifier {
+                ImportSpecifier::Named(named) => Some(imported_name(named)),
+                _ => None,
+            })
+            .collect();
+
+        for (index, specifier) in import.specifiers.iter().enumerate() {
+            let named = match specifier {
+                ImportSpecifier::Named(named) => named,
+                ImportSpecifier::Namespace(namespace) => {
+                    namespaces.push(namespace.local.sym.to_string());
+                    continue;
+                }
+                ImportSpecifier::Default(_) => continue,
+            };
+            let Some(change) = change_for(migrations, &imported_name(named)) else {
+                continue;
+            };
+            locals.insert(named.local.sym.to_string(), (change, named.imported.is_some()));
+
+            match change {
+                Change::Rename { from, to } => {
+                    applied.push(format!("`{}` is now `{}`", from, to));
+                    edits.push(if imported_names.iter().any(|name| name == to) {
+                        // The new name is imported already, so the old specifier goes away
+                        TextEdit {
+                            span: specifier_removal(import, index),
+                            replacement: String::new(),
+                        }
+                    } else {
+                        TextEdit {
+                            span: named.imported.as_ref().map(|imported| imported.span()).unwrap_or(named.span),
+                            replacement: to.to_string(),
+                        }
+                    });
+                }
+                Change::Removed { name, hint } => skipped.push(format!(
+                    "line {}: `{}` was removed, {}",
+                    cm.lookup_char_pos(named.span.lo).line,
+                    name,
+                    hint
+                )),
+            }
+        }
+    }
+
+    let mut references = ReferenceRewriter {
+        locals: &locals,
+        namespaces: &namespaces,
+        migrations,
+        edits: vec![],
+    };
+    module.visit_with(&mut references);
+    edits.extend(references.edits);
+
+    applied.sort();
+    applied.dedup();
+    let fix = Fix {
+        description: "Migrate rask-ui APIs".to_string(),
+        edits,
+    };
+    let (output, _) = apply_edits(source.text, source.start_pos, &[Some(&fix)]);
+
+    Ok(CodemodResult {
+        path: path.to_path_buf(),
+        source: source.text.to_string(),
+        output,
+        applied,
+        skipped,
+    })
+}
+
+fn imported_name(named: &ImportNamedSpecifier) -> String {
+    match &named.imported {
+        Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+        Some(ModuleExportName::Str(str)) => str.value.to_string_lossy().into_owned(),
+        None => named.local.sym.to_string(),
+    }
+}
+
+/// The latest change made to an export, a later release may change a renamed export again
+fn change_for(migrations: &[&Migration], name: &str) -> Option<&'static Change> {
+    let mut name = name.to_string();
+    let mut found = None;
+    for migration in migrations {
+        for change in migration.changes {
+            let changed = match change {
+                Change::Rename { from, .. } => *from,
+                Change::Removed { name, .. } => *name,
+            };
+            if changed == name {
+                if let Change::Rename { to, .. } = change {
+                    name = to.to_string();
+                }
+                found = Some(change);
+            }
+        }
+    }
+    found
+}
+
+/// Span removing a specifier together with the comma separating it from its neighbour
+fn specifier_removal(import: &ImportDecl, index: usize) -> Span {
+    let span = import.specifiers[index].span();
+    match (import.specifiers.get(index + 1), index.checked_sub(1)) {
+        (Some(next), _) => Span::new(span.lo, next.span().lo),
+        (None, Some(previous)) => Span::new(import.specifiers[previous].span().hi, span.hi),
+        (None, None) => span,
+    }
+}
+
+/// Rewrites the uses of changed exports, through their local names or a namespace import
+struct ReferenceRewriter<'a> {
+    locals: &'a HashMap<String, (&'static Change, bool)>,
+    namespaces: &'a [String],
+    migrations: &'a [&'a Migration],
+    edits: Vec<TextEdit>,
+}
+
+impl ReferenceRewriter<'_> {
+    /// The new name of a local binding, aliased imports keep their local name
+    fn renamed(&self, local: &str) -> Option<&'static str> {
+        match self.locals.get(local) {
+            Some((Change::Rename { to, .. }, false)) => Some(to),
+            _ => None,
+        }
+    }
+}
+
+impl Visit for ReferenceRewriter<'_> {
+    noop_visit_type!();
+
+    // The specifiers are rewritten with the import
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if let Some(to) = self.renamed(&ident.sym) {
+            self.edits.push(TextEdit {
+                span: ident.span,
+                replacement: to.to_string(),
+            });
+        }
+    }
+
+    fn visit_prop(&mut self, prop: &Prop) {
+        // `{ createState }` keeps its key when the value is renamed
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(to) = self.renamed(&ident.sym) {
+                self.edits.push(TextEdit {
+                    span: ident.span,
+                    replacement: format!("{}: {}", ident.sym, to),
+                });
+                return;
+            }
+        }
+        prop.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        // `Rask.createState` through a namespace import
+        if let (Expr::Ident(obj), MemberProp::Ident(prop)) = (&*member.obj, &member.prop) {
+            if self.namespaces.iter().any(|namespace| namespace.as_str() == &*obj.sym) {
+                if let Some(Change::Rename { to, .. }) = change_for(self.migrations, &prop.sym) {
+                    self.edits.push(TextEdit {
+                        span: prop.span,
+                        replacement: to.to_string(),
+                    });
+                }
+            }
+        }
+        member.visit_children_with(self);
+    }
+}
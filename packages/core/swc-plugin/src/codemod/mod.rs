@@ -0,0 +1,22 @@
+pub mod convert;
+pub mod migrations;
+pub mod react;
+
+use std::path::PathBuf;
+
+/// The outcome of running a codemod over one file
+pub struct CodemodResult {
+    pub path: PathBuf,
+    pub source: String,
+    pub output: String,
+    /// Descriptions of the rewrites that were applied
+    pub applied: Vec<String>,
+    /// What could not be rewritten and why
+    pub skipped: Vec<String>,
+}
+
+impl CodemodResult {
+    pub fn changed(&self) -> bool {
+        self.source != self.output
+    }
+}
@@ -0,0 +1,880 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    io,
+    path::Path,
+};
+
+use swc_core::common::{sync::Lrc, SourceMap, Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use super::CodemodResult;
+use crate::analysis::{callee_method, callee_name, unwrap_expr};
+use crate::compile::{parse_source, syntax_for};
+use crate::diagnostics::{Fix, TextEdit};
+use crate::fix::apply_edits;
+use crate::manifest::ComponentKind;
+use crate::refactor::{find_components, indent_unit, render_dependency, ComponentDecl, SourceText};
+use crate::rules::stale_setup_read::destructured_props_fix;
+
+const REACT_SOURCE: &str = "react";
+const RASK_SOURCE: &str = "rask-ui";
+
+/// React hooks with a Rask equivalent, removed from the React import once converted
+const CONVERTED_HOOKS: &[&str] = &[
+    "useState",
+    "useEffect",
+    "useLayoutEffect",
+    "useMemo",
+    "useCallback",
+    "useContext",
+    "useRef",
+];
+
+/// Rewrite the React function components of a file into Rask components.
+/// Hooks move into setup, everything else moves into the render function, and what can't be
+/// converted gets a `TODO(rask)` comment and a report entry.
+pub fn run(cm: &Lrc<SourceMap>, path: &Path) -> io::Result<CodemodResult> {
+    let file = cm.load_file(path)?;
+    let mut errors = vec![];
+    let Some(module) = parse_source(&file, syntax_for(path), None, &mut errors) else {
+        let message = errors
+            .first()
+            .map(|error| error.message.clone())
+            .unwrap_or_else(|| "could not parse the file".to_string());
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    };
+
+    let source = SourceText {
+        text: file.src.as_str(),
+        start_pos: file.start_pos,
+    };
+    let line = |span: Span| cm.lookup_char_pos(span.lo).line;
+
+    let mut imports = BTreeSet::new();
+    let mut kept = BTreeSet::new();
+    let mut fixes = vec![];
+    let mut applied = vec![];
+    let mut skipped = vec![];
+
+    // React components return their output directly, which classifies them as stateless
+    for component in find_components(&module) {
+        if component.kind != ComponentKind::Stateless {
+            continue;
+        }
+        let mut migration = Migration {
+            source,
+            edits: vec![],
+            todos: vec![],
+            imports: &mut imports,
+            kept: &mut kept,
+        };
+        match migration.component(&component) {
+            Ok(Some(fix)) => {
+                applied.push(format!("`{}`: {}", component.name, fix.description));
+                fixes.push(fix);
+            }
+            Ok(None) => {}
+            Err(reason) => {
+                // Nothing of the component is rewritten, so its hooks and their imports stay as they are
+                migration.todos.clear();
+                migration.keep_hooks(&component.func);
+                skipped.push(format!("line {}: `{}` {}", line(component.span), component.name, reason));
+            }
+        }
+        for (span, todo) in migration.todos {
+            skipped.push(format!("line {}: {}", line(span), todo));
+        }
+    }
+
+    if let Some((fix, todos)) = import_fix(&module, source, &imports, &kept) {
+        for (span, todo) in todos {
+            skipped.push(format!("line {}: {}", line(span), todo));
+        }
+        fixes.push(fix);
+    }
+
+    let refs: Vec<Option<&Fix>> = fixes.iter().map(Some).collect();
+    let (output, _) = apply_edits(source.text, source.start_pos, &refs);
+
+    Ok(CodemodResult {
+        path: path.to_path_buf(),
+        source: source.text.to_string(),
+        output,
+        applied,
+        skipped,
+    })
+}
+
+/// How a top-level statement of a React component converts into Rask setup
+enum SetupStmt<'a> {
+    /// `const [value, setValue] = useState(init)`
+    State {
+        value: &'a Ident,
+        setter: Option<&'a Ident>,
+        init: Option<&'a Expr>,
+    },
+    /// `const value = useMemo(factory, deps)`
+    Memo { value: &'a Ident, factory: &'a Expr },
+    /// `useEffect(callback, deps)` or `useLayoutEffect(callback, deps)`
+    Effect {
+        hook: &'a str,
+        callback: &'a Expr,
+        deps: Option<&'a Expr>,
+    },
+    /// `const handler = useCallback(callback, deps)`
+    Callback { name: &'a Ident, callback: &'a Expr },
+    /// `const value = useContext(Context)`
+    Context { name: &'a Ident, context: &'a Expr },
+    /// `const ref = useRef(init)`
+    Ref { name: &'a Ident, init: Option<&'a Expr> },
+    /// Any other hook, kept in setup as is
+    Custom { hook: &'a str },
+}
+
+impl SetupStmt<'_> {
+    fn hook(&self) -> &str {
+        match self {
+            SetupStmt::State { .. } => "useState",
+            SetupStmt::Memo { .. } => "useMemo",
+            SetupStmt::Callback { .. } => "useCallback",
+            SetupStmt::Context { .. } => "useContext",
+            SetupStmt::Ref { .. } => "useRef",
+            SetupStmt::Effect { hook, .. } | SetupStmt::Custom { hook } => hook,
+        }
+    }
+}
+
+struct Migration<'a, 'b> {
+    source: SourceText<'a>,
+    /// Rewrites of references to converted hooks, applied in place or folded into rewritten statements
+    edits: Vec<TextEdit>,
+    todos: Vec<(Span, String)>,
+    imports: &'b mut BTreeSet<&'static str>,
+    /// React hooks still called after the conversion, whose import is kept
+    kept: &'b mut BTreeSet<String>,
+}
+
+impl Migration<'_, '_> {
+    fn keep_hooks(&mut self, func: &Function) {
+        let mut hooks = NestedHookFinder { hooks: vec![] };
+        func.body.visit_with(&mut hooks);
+        self.kept.extend(hooks.hooks.into_iter().map(|(_, hook)| hook));
+    }
+
+    /// Convert a component, `Ok(None)` when it uses no hooks and already is a valid stateless component
+    fn component(&mut self, component: &ComponentDecl) -> Result<Option<Fix>, String> {
+        let func = &component.func;
+        let Some(body) = &func.body else {
+            return Ok(None);
+        };
+        let Some((last, stmts)) = body.stmts.split_last() else {
+            return Ok(None);
+        };
+
+        let mut setup: Vec<Option<SetupStmt>> = stmts.iter().map(setup_stmt).collect();
+        if setup.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = last else {
+            return Err("does not end with a return statement, convert it by hand".to_string());
+        };
+
+        // Hooks called after render statements move up into setup, unless they use what those declare
+        let mut render_dependent = HashSet::new();
+        if let Some(first_render) = setup.iter().position(Option::is_none) {
+            for index in first_render + 1..stmts.len() {
+                let Some(hook) = setup[index].as_ref().map(|conversion| conversion.hook().to_string()) else {
+                    continue;
+                };
+                let render = stmts[first_render..index]
+                    .iter()
+                    .zip(&setup[first_render..index])
+                    .filter_map(|(stmt, conversion)| conversion.is_none().then_some(stmt));
+                if let Some(name) = render_dependency(render, &stmts[index]) {
+                    self.todos.push((
+                        stmts[index].span(),
+                        format!(
+                            "`{}` uses `{}`, which is computed while rendering, move the hook into setup by hand",
+                            hook, name
+                        ),
+                    ));
+                    setup[index] = None;
+                    render_dependent.insert(index);
+                    self.kept.insert(hook);
+                }
+            }
+        }
+
+        let mut names = Names::default();
+        for stmt in setup.iter().flatten() {
+            match stmt {
+                SetupStmt::State { value, setter, .. } => {
+                    names.state.insert(value.sym.to_string());
+                    names.declarations.insert(value.span);
+                    if let Some(setter) = setter {
+                        names.setters.insert(setter.sym.to_string(), value.sym.to_string());
+                        names.declarations.insert(setter.span);
+                    }
+                }
+                SetupStmt::Memo { value, .. } => {
+                    names.derived.insert(value.sym.to_string());
+                    names.declarations.insert(value.span);
+                }
+                _ => {}
+            }
+        }
+
+        let mut declared = DeclaredNames::default();
+        func.visit_with(&mut declared);
+        for (object, used) in [("state", !names.state.is_empty()), ("derived", !names.derived.is_empty())] {
+            if used && declared.names.contains(object) {
+                return Err(format!("already declares `{}`, which the converted hooks are stored in", object));
+            }
+        }
+
+        let mut references = ReferenceRewriter {
+            names: &names,
+            edits: vec![],
+            bare_call: None,
+            shadowed: false,
+        };
+        func.body.visit_with(&mut references);
+        if references.shadowed {
+            return Err("shadows the name of a `useState` or `useMemo` value, convert it by hand".to_string());
+        }
+        self.edits = references.edits;
+
+        // Destructured props are only read once in Rask, so they become `props.<name>` reads
+        let mut param_edits = vec![];
+        if let Some(Pat::Object(object_pat)) = func.params.first().map(|param| &param.pat) {
+            match destructured_props_fix(func, object_pat) {
+                Some(fix) => {
+                    for edit in fix.edits {
+                        if edit.span.lo < body.span.lo {
+                            param_edits.push(edit);
+                        } else {
+                            self.edits.push(edit);
+                        }
+                    }
+                }
+                None => self.todos.push((
+                    object_pat.span,
+                    "props are destructured with defaults or a rest element, read them from `props` instead"
+                        .to_string(),
+                )),
+            }
+        }
+
+        let mut texts = vec![];
+        let mut state_emitted = false;
+        let mut derived_emitted = false;
+        for (index, (stmt, conversion)) in stmts.iter().zip(&setup).enumerate() {
+            let text = match conversion {
+                Some(SetupStmt::State { .. }) if state_emitted => Some(String::new()),
+                Some(SetupStmt::State { .. }) => {
+                    state_emitted = true;
+                    Some(self.state_text(&setup))
+                }
+                Some(SetupStmt::Memo { .. }) if derived_emitted => Some(String::new()),
+                Some(SetupStmt::Memo { .. }) => {
+                    derived_emitted = true;
+                    Some(self.derived_text(&setup))
+                }
+                Some(conversion) => Some(self.setup_text(stmt, conversion)),
+                None if render_dependent.contains(&index) => None,
+                None => {
+                    let mut hooks = NestedHookFinder { hooks: vec![] };
+                    stmt.visit_with(&mut hooks);
+                    for (span, hook) in hooks.hooks {
+                        self.todos.push((
+                            span,
+                            format!("`{}` is called conditionally, Rask hooks can only be called in setup", hook),
+                        ));
+                        self.kept.insert(hook);
+                    }
+                    None
+                }
+            };
+            texts.push(text);
+        }
+
+        let mut edits = param_edits;
+        let mut rewritten = vec![];
+        let first_render = setup.iter().position(Option::is_none);
+        let mut moved = String::new();
+        let indent = self
+            .source
+            .indent_at(first_render.map(|index| stmts[index].span().lo).unwrap_or(last.span().lo));
+        // Render statements move one level deeper, into the render function
+        let unit = indent_unit(self.source.indent_at(func.span.lo), indent);
+        let mut removed = vec![];
+
+        for (index, (stmt, text)) in stmts.iter().zip(texts).enumerate() {
+            let span = stmt.span();
+            let Some(text) = text else {
+                for (_, todo) in self.todos.iter().filter(|(todo_span, _)| span.contains(*todo_span)) {
+                    edits.push(TextEdit {
+                        span: span.shrink_to_lo(),
+                        replacement: format!("// TODO(rask): {}\n{}{}", todo, self.source.indent_at(span.lo), unit),
+                    });
+                }
+                continue;
+            };
+            rewritten.push(span);
+
+            if text.is_empty() || first_render.is_some_and(|first| index > first) {
+                // Setup statements declared between render statements move up into setup
+                if !text.is_empty() {
+                    moved.push_str(&text);
+                    moved.push('\n');
+                    moved.push_str(indent);
+                }
+                let span = self.source.whole_lines(span);
+                removed.push(span);
+                edits.push(TextEdit {
+                    span,
+                    replacement: String::new(),
+                });
+            } else {
+                edits.push(TextEdit { span, replacement: text });
+            }
+        }
+
+        match first_render {
+            Some(first) => {
+                let render = Span::new(stmts[first].span().lo, last.span().hi);
+                for (pos, _) in self.source.line_starts(render, stmts[first..].iter().chain([last])) {
+                    if !removed.iter().any(|span| span.lo <= pos && pos < span.hi) {
+                        edits.push(TextEdit {
+                            span: Span::new(pos, pos),
+                            replacement: unit.to_string(),
+                        });
+                    }
+                }
+                // Ahead of a TODO comment inserted at the same position, which belongs inside render
+                edits.insert(
+                    0,
+                    TextEdit {
+                        span: stmts[first].span().shrink_to_lo(),
+                        replacement: format!("{}return () => {{\n{}{}", moved, indent, unit),
+                    },
+                );
+                edits.push(TextEdit {
+                    span: last.span().shrink_to_hi(),
+                    replacement: format!("\n{}}};", indent),
+                });
+            }
+            None => edits.push(TextEdit {
+                span: arg.span().shrink_to_lo(),
+                replacement: "() => ".to_string(),
+            }),
+        }
+
+        // References inside rewritten statements were folded into their new text
+        edits.extend(
+            std::mem::take(&mut self.edits)
+                .into_iter()
+                .filter(|edit| !rewritten.iter().any(|span| span.contains(edit.span))),
+        );
+
+        Ok(Some(Fix {
+            description: "Converted to a Rask component".to_string(),
+            edits,
+        }))
+    }
+
+    /// Source text of a span with the reference rewrites inside it applied
+    fn text(&self, span: Span) -> String {
+        let mut edits: Vec<&TextEdit> = self.edits.iter().filter(|edit| span.contains(edit.span)).collect();
+        edits.sort_by_key(|edit| (edit.span.lo, edit.span.hi));
+
+        let mut text = String::new();
+        let mut pos = span.lo;
+        for edit in edits {
+            if edit.span.lo < pos {
+                continue;
+            }
+            text.push_str(self.source.slice(Span::new(pos, edit.span.lo)).unwrap_or_default());
+            text.push_str(&edit.replacement);
+            pos = edit.span.hi;
+        }
+        text.push_str(self.source.slice(Span::new(pos, span.hi)).unwrap_or_default());
+        text
+    }
+
+    /// Every `useState` pair of the component merged into one state object
+    fn state_text(&mut self, setup: &[Option<SetupStmt>]) -> String {
+        self.imports.insert("useState");
+        let fields: Vec<String> = setup
+            .iter()
+            .flatten()
+            .filter_map(|stmt| match stmt {
+                SetupStmt::State { value, init, .. } => Some(format!(
+                    "{}: {}",
+                    value.sym,
+                    match init {
+                        Some(init @ (Expr::Arrow(_) | Expr::Fn(_))) => format!("({})()", self.text(init.span())),
+                        Some(init) => self.text(init.span()),
+                        None => "undefined".to_string(),
+                    }
+                )),
+                _ => None,
+            })
+            .collect();
+        format!("const state = useState({{ {} }});", fields.join(", "))
+    }
+
+    /// Every `useMemo` of the component merged into one derived object
+    fn derived_text(&mut self, setup: &[Option<SetupStmt>]) -> String {
+        self.imports.insert("useDerived");
+        let fields: Vec<String> = setup
+            .iter()
+            .flatten()
+            .filter_map(|stmt| match stmt {
+                SetupStmt::Memo { value, factory } => Some(format!("{}: {}", value.sym, self.text(factory.span()))),
+                _ => None,
+            })
+            .collect();
+        format!("const derived = useDerived({{ {} }});", fields.join(", "))
+    }
+
+    fn setup_text(&mut self, stmt: &Stmt, conversion: &SetupStmt) -> String {
+        match conversion {
+            SetupStmt::Effect { callback, deps, .. } => {
+                // Rask effects track what they read, an empty dependency list means run once on mount
+                let mount = matches!(deps, Some(Expr::Array(array)) if array.elems.is_empty()) && !returns_cleanup(callback);
+                let hook = if mount { "useMountEffect" } else { "useEffect" };
+                self.imports.insert(hook);
+                format!("{}({});", hook, self.text(callback.span()))
+            }
+            SetupStmt::Callback { name, callback } => format!("const {} = {};", name.sym, self.text(callback.span())),
+            SetupStmt::Context { name, context } => format!("const {} = {}.use();", name.sym, self.text(context.span())),
+            SetupStmt::Ref { name, init } => {
+                self.imports.insert("useRef");
+                match init {
+                    Some(init) if !is_empty_value(init) => format!(
+                        "const {} = useRef();\n{}{}.current = {};",
+                        name.sym,
+                        self.source.indent_at(stmt.span().lo),
+                        name.sym,
+                        self.text(init.span())
+                    ),
+                    _ => format!("const {} = useRef();", name.sym),
+                }
+            }
+            SetupStmt::Custom { hook } => {
+                self.todos.push((
+                    stmt.span(),
+                    format!("`{}` is not a Rask hook, check that it only reads reactive values lazily", hook),
+                ));
+                format!(
+                    "// TODO(rask): `{}` is not a Rask hook, check that it only reads reactive values lazily\n{}{}",
+                    hook,
+                    self.source.indent_at(stmt.span().lo),
+                    self.text(stmt.span())
+                )
+            }
+            SetupStmt::State { .. } | SetupStmt::Memo { .. } => unreachable!("merged into a single declaration"),
+        }
+    }
+}
+
+fn setup_stmt(stmt: &Stmt) -> Option<SetupStmt<'_>> {
+    match stmt {
+        Stmt::Decl(Decl::Var(var_decl)) if var_decl.decls.len() == 1 => {
+            let decl = &var_decl.decls[0];
+            let Some(Expr::Call(call)) = decl.init.as_deref().map(unwrap_expr) else {
+                return None;
+            };
+            let hook = hook_name(call)?;
+            let arg = |index: usize| call.args.get(index).filter(|arg| arg.spread.is_none()).map(|arg| &*arg.expr);
+
+            Some(match (hook, &decl.name) {
+                ("useState", Pat::Array(array)) if array.elems.len() <= 2 => {
+                    let mut elems = array.elems.iter().map(|elem| match elem {
+                        Some(Pat::Ident(binding)) => Some(&binding.id),
+                        _ => None,
+                    });
+                    match (elems.next().flatten(), elems.next()) {
+                        (Some(value), setter) => SetupStmt::State {
+                            value,
+                            setter: setter.flatten(),
+                            init: arg(0),
+                        },
+                        _ => SetupStmt::Custom { hook },
+                    }
+                }
+                ("useMemo", Pat::Ident(binding)) => match arg(0) {
+                    Some(factory) => SetupStmt::Memo {
+                        value: &binding.id,
+                        factory,
+                    },
+                    None => SetupStmt::Custom { hook },
+                },
+                ("useCallback", Pat::Ident(binding)) => match arg(0) {
+                    Some(callback) => SetupStmt::Callback {
+                        name: &binding.id,
+                        callback,
+                    },
+                    None => SetupStmt::Custom { hook },
+                },
+                ("useContext", Pat::Ident(binding)) => match arg(0) {
+                    Some(context) => SetupStmt::Context {
+                        name: &binding.id,
+                        context,
+                    },
+                    None => SetupStmt::Custom { hook },
+                },
+                ("useRef", Pat::Ident(binding)) => SetupStmt::Ref {
+                    name: &binding.id,
+                    init: arg(0),
+                },
+                _ => SetupStmt::Custom { hook },
+            })
+        }
+        Stmt::Expr(expr_stmt) => {
+            let Expr::Call(call) = unwrap_expr(&expr_stmt.expr) else {
+                return None;
+            };
+            let hook = hook_name(call)?;
+            let callback = call.args.first().filter(|arg| arg.spread.is_none());
+            match (hook, callback) {
+                ("useEffect" | "useLayoutEffect", Some(callback)) => Some(SetupStmt::Effect {
+                    hook,
+                    callback: &callback.expr,
+                    deps: call.args.get(1).map(|arg| &*arg.expr),
+                }),
+                _ => Some(SetupStmt::Custom { hook }),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Name of a hook call, also through the `React.useState` namespace
+fn hook_name(call: &CallExpr) -> Option<&str> {
+    let name = match callee_name(call) {
+        Some(name) => name,
+        None => {
+            let (obj, method) = callee_method(call)?;
+            if !matches!(unwrap_expr(obj), Expr::Ident(ident) if &*ident.sym == "React") {
+                return None;
+            }
+            method
+        }
+    };
+    name.strip_prefix("use")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+        .then_some(name)
+}
+
+fn is_empty_value(expr: &Expr) -> bool {
+    match unwrap_expr(expr) {
+        Expr::Lit(Lit::Null(_)) => true,
+        Expr::Ident(ident) => &*ident.sym == "undefined",
+        _ => false,
+    }
+}
+
+/// Whether an effect callback returns a cleanup function
+fn returns_cleanup(callback: &Expr) -> bool {
+    let body = match unwrap_expr(callback) {
+        Expr::Arrow(arrow) => match &*arrow.body {
+            BlockStmtOrExpr::BlockStmt(block) => block,
+            BlockStmtOrExpr::Expr(_) => return true,
+        },
+        Expr::Fn(fn_expr) => match &fn_expr.function.body {
+            Some(block) => block,
+            None => return false,
+        },
+        _ => return true,
+    };
+    body.stmts
+        .iter()
+        .any(|stmt| matches!(stmt, Stmt::Return(ReturnStmt { arg: Some(_), .. })))
+}
+
+/// Replace the converted hooks in the React import with a Rask import, keeping the hooks still called
+fn import_fix(
+    module: &Module,
+    source: SourceText,
+    imports: &BTreeSet<&'static str>,
+    kept: &BTreeSet<String>,
+) -> Option<(Fix, Vec<(Span, String)>)> {
+    let mut edits = vec![];
+    let mut todos = vec![];
+    let mut rask_import_added = imports.is_empty();
+
+    // Names that are already imported from rask-ui don't need importing again
+    let existing: HashSet<String> = module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if &*import.src.value == RASK_SOURCE => Some(import),
+            _ => None,
+        })
+        .flat_map(|import| import.specifiers.iter())
+        .filter_map(|specifier| match specifier {
+            ImportSpecifier::Named(named) => Some(named.local.sym.to_string()),
+            _ => None,
+        })
+        .collect();
+    let names: Vec<&str> = imports.iter().copied().filter(|name| !existing.contains(*name)).collect();
+    let rask_import = format!("import {{ {} }} from \"{}\";", names.join(", "), RASK_SOURCE);
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        if &*import.src.value != REACT_SOURCE || import.type_only {
+            continue;
+        }
+
+        let mut default = None;
+        let mut named = vec![];
+        let mut unsupported = vec![];
+        for specifier in &import.specifiers {
+            match specifier {
+                ImportSpecifier::Named(specifier) if kept.contains(&*specifier.local.sym) => {
+                    named.push(source.slice(specifier.span).unwrap_or_default().to_string());
+                }
+                ImportSpecifier::Named(specifier) if CONVERTED_HOOKS.contains(&&*specifier.local.sym) => {}
+                ImportSpecifier::Named(specifier) => {
+                    named.push(source.slice(specifier.span).unwrap_or_default().to_string());
+                    unsupported.push(specifier.local.sym.to_string());
+                }
+                ImportSpecifier::Default(specifier) => {
+                    default = Some(specifier.local.sym.to_string());
+                    unsupported.push(specifier.local.sym.to_string());
+                }
+                ImportSpecifier::Namespace(specifier) => unsupported.push(specifier.local.sym.to_string()),
+            }
+        }
+
+        let remaining = match (&default, named.is_empty()) {
+            _ if import.specifiers.iter().any(|specifier| matches!(specifier, ImportSpecifier::Namespace(_))) => {
+                Some(source.slice(import.span).unwrap_or_default().to_string())
+            }
+            (None, true) => None,
+            (Some(default), true) => Some(format!("import {} from \"{}\";", default, REACT_SOURCE)),
+            (None, false) => Some(format!("import {{ {} }} from \"{}\";", named.join(", "), REACT_SOURCE)),
+            (Some(default), false) => Some(format!(
+                "import {}, {{ {} }} from \"{}\";",
+                default,
+                named.join(", "),
+                REACT_SOURCE
+            )),
+        };
+
+        let mut replacement = vec![];
+        match remaining {
+            Some(remaining) if unsupported.is_empty() => replacement.push(remaining),
+            Some(remaining) => {
+                let todo = format!("`{}` imported from react have no Rask equivalent", unsupported.join("`, `"));
+                replacement.push(format!("// TODO(rask): {}\n{}", todo, remaining));
+                todos.push((import.span, todo));
+            }
+            None => {}
+        }
+        if !rask_import_added && !names.is_empty() {
+            replacement.push(rask_import.clone());
+        }
+        rask_import_added = true;
+
+        edits.push(TextEdit {
+            span: if replacement.is_empty() {
+                source.whole_lines(import.span)
+            } else {
+                import.span
+            },
+            replacement: replacement.join("\n"),
+        });
+    }
+
+    // Components calling `React.useState` may have no named import to replace
+    if !rask_import_added && !names.is_empty() {
+        edits.push(TextEdit {
+            span: module.body.first().map(|item| item.span()).unwrap_or(module.span).shrink_to_lo(),
+            replacement: format!("{}\n", rask_import),
+        });
+    }
+
+    (!edits.is_empty()).then(|| {
+        (
+            Fix {
+                description: "Import the Rask hooks".to_string(),
+                edits,
+            },
+            todos,
+        )
+    })
+}
+
+#[derive(Default)]
+struct Names {
+    state: HashSet<String>,
+    derived: HashSet<String>,
+    /// Setter name to the state value it writes
+    setters: HashMap<String, String>,
+    /// Spans of the bindings declared by the converted hooks
+    declarations: HashSet<Span>,
+}
+
+/// Rewrites references to `useState` and `useMemo` values into reads and writes of the state objects
+struct ReferenceRewriter<'a> {
+    names: &'a Names,
+    edits: Vec<TextEdit>,
+    /// A setter call whose value is unused, so the assignment needs no parentheses
+    bare_call: Option<Span>,
+    shadowed: bool,
+}
+
+impl ReferenceRewriter<'_> {
+    fn replacement(&self, name: &str) -> Option<String> {
+        if self.names.state.contains(name) {
+            Some(format!("state.{}", name))
+        } else if self.names.derived.contains(name) {
+            Some(format!("derived.{}", name))
+        } else {
+            // Setters passed along may still be called with an updater function
+            self.names.setters.get(name).map(|value| {
+                format!(
+                    "(value) => (state.{0} = typeof value === \"function\" ? value(state.{0}) : value)",
+                    value
+                )
+            })
+        }
+    }
+}
+
+impl Visit for ReferenceRewriter<'_> {
+    noop_visit_type!();
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        if !self.names.declarations.contains(&binding.id.span) && self.replacement(&binding.id.sym).is_some() {
+            self.shadowed = true;
+        }
+    }
+
+    fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
+        self.bare_call = Some(expr_stmt.expr.span());
+        expr_stmt.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        if let BlockStmtOrExpr::Expr(expr) = &*arrow.body {
+            self.bare_call = Some(expr.span());
+        }
+        arrow.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let setter = match &call.callee {
+            Callee::Expr(callee) => match &**callee {
+                Expr::Ident(ident) => self.names.setters.get(&*ident.sym),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(value) = setter else {
+            call.visit_children_with(self);
+            return;
+        };
+
+        let bare = self.bare_call == Some(call.span);
+        let (open, close) = if bare { ("", "") } else { ("(", ")") };
+        match call.args.first() {
+            None => self.edits.push(TextEdit {
+                span: call.span,
+                replacement: format!("{}state.{} = undefined{}", open, value, close),
+            }),
+            Some(arg) => {
+                // An updater function receives the current value
+                let updater = matches!(unwrap_expr(&arg.expr), Expr::Arrow(_) | Expr::Fn(_));
+                self.edits.push(TextEdit {
+                    span: Span::new(call.span.lo, arg.expr.span().lo),
+                    replacement: if updater {
+                        format!("{}state.{} = (", open, value)
+                    } else {
+                        format!("{}state.{} = ", open, value)
+                    },
+                });
+                self.edits.push(TextEdit {
+                    span: Span::new(arg.expr.span().hi, call.span.hi),
+                    replacement: if updater {
+                        format!(")(state.{}){}", value, close)
+                    } else {
+                        close.to_string()
+                    },
+                });
+                arg.expr.visit_with(self);
+            }
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if let Some(replacement) = self.replacement(&ident.sym) {
+            self.edits.push(TextEdit {
+                span: ident.span,
+                replacement,
+            });
+        }
+    }
+
+    fn visit_prop(&mut self, prop: &Prop) {
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(replacement) = self.replacement(&ident.sym) {
+                self.edits.push(TextEdit {
+                    span: ident.span,
+                    replacement: format!("{}: {}", ident.sym, replacement),
+                });
+                return;
+            }
+        }
+        prop.visit_children_with(self);
+    }
+
+    fn visit_jsx_element_name(&mut self, name: &JSXElementName) {
+        // Lowercase names are intrinsic elements, not references to a binding
+        if let JSXElementName::Ident(ident) = name {
+            if ident.sym.starts_with(|c: char| c.is_ascii_uppercase()) {
+                self.visit_ident(ident);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct DeclaredNames {
+    names: HashSet<String>,
+}
+
+impl Visit for DeclaredNames {
+    noop_visit_type!();
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.names.insert(binding.id.sym.to_string());
+    }
+}
+
+/// Hooks called inside a render statement, where Rask doesn't allow them
+struct NestedHookFinder {
+    hooks: Vec<(Span, String)>,
+}
+
+impl Visit for NestedHookFinder {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(hook) = hook_name(call) {
+            self.hooks.push((call.span, hook.to_string()));
+        }
+        call.visit_children_with(self);
+    }
+}
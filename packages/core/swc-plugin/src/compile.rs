@@ -0,0 +1,135 @@
+use std::{fmt, path::Path};
+
+use swc_core::common::{
+    comments::{Comments, SingleThreadedComments},
+    sync::Lrc,
+    FileName, SourceFile, SourceMap, Spanned, GLOBALS,
+};
+use swc_core::ecma::{
+    ast::*,
+    codegen::to_code_default,
+    parser::{parse_file_as_module, EsSyntax, Syntax, TsSyntax},
+    visit::VisitMutWith,
+};
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::explain::Explanation;
+use crate::lazy;
+use crate::location::Location;
+use crate::manifest::ComponentManifest;
+use crate::{Config, RaskComponentTransform};
+
+pub const PARSE_ERROR_RULE: &str = "parse-error";
+
+/// The result of compiling a single module natively
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub code: String,
+    /// Warnings reported while compiling, errors are returned as [`Diagnostics`] instead
+    pub diagnostics: Vec<Diagnostic>,
+    pub components: Vec<ComponentManifest>,
+    pub explanations: Vec<Explanation>,
+}
+
+/// Diagnostics of a module that failed to compile, including every warning reported with the errors
+#[derive(Debug, Clone)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in self.0.iter().filter(|diagnostic| diagnostic.severity == Severity::Error) {
+            writeln!(f, "[rask/{}] {}", diagnostic.rule, diagnostic.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// Parser syntax for a file based on its extension
+pub fn syntax_for(path: &Path) -> Syntax {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts") | Some("tsx") | Some("mts") | Some("cts") => Syntax::Typescript(TsSyntax {
+            tsx: true,
+            ..Default::default()
+        }),
+        _ => Syntax::Es(EsSyntax {
+            jsx: true,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Parse a source file into a module, reporting syntax errors as diagnostics
+pub fn parse_source(
+    source: &SourceFile,
+    syntax: Syntax,
+    comments: Option<&dyn Comments>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Module> {
+    let mut recovered = vec![];
+    let module = parse_file_as_module(source, syntax, EsVersion::latest(), comments, &mut recovered);
+
+    for error in recovered {
+        diagnostics.push(Diagnostic::error(PARSE_ERROR_RULE, error.span(), error.kind().msg()));
+    }
+
+    match module {
+        Ok(module) => Some(module),
+        Err(error) => {
+            diagnostics.push(Diagnostic::error(PARSE_ERROR_RULE, error.span(), error.kind().msg()));
+            None
+        }
+    }
+}
+
+/// Compile the components of a module without loading the wasm plugin.
+/// The source is expected to be the output of the Inferno JSX transform, as with the plugin.
+pub fn transform_source(source: &str, filename: &str, config: &Config) -> Result<Output, Diagnostics> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let file = cm.new_source_file(Lrc::new(FileName::Custom(filename.to_string())), source.to_string());
+    transform_file(&cm, &file, filename, config)
+}
+
+/// Compile a file already loaded into the source map, so the caller can resolve the spans of the output
+pub fn transform_file(
+    cm: &Lrc<SourceMap>,
+    file: &SourceFile,
+    filename: &str,
+    config: &Config,
+) -> Result<Output, Diagnostics> {
+    let comments = SingleThreadedComments::default();
+
+    let mut diagnostics = vec![];
+    let Some(mut module) = parse_source(file, syntax_for(Path::new(filename)), Some(&comments), &mut diagnostics)
+    else {
+        return Err(Diagnostics(diagnostics));
+    };
+
+    let mut transform = RaskComponentTransform::new(config.clone())
+        .with_file(Some(filename.to_string()))
+        .with_lazy_imports(lazy::annotated_imports(&module, &comments));
+    GLOBALS.set(&Default::default(), || module.visit_mut_with(&mut transform));
+    transform.add_comments(&comments, &**cm, module.span.lo);
+
+    diagnostics.extend(transform.diagnostics);
+    if diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error) {
+        return Err(Diagnostics(diagnostics));
+    }
+
+    let mut components = transform.components;
+    for component in &mut components {
+        component.location = Location::from_span(&**cm, component.span);
+    }
+    let mut explanations = transform.explanations;
+    for explanation in &mut explanations {
+        explanation.location = Location::from_span(&**cm, explanation.span);
+    }
+
+    Ok(Output {
+        code: to_code_default(cm.clone(), Some(&comments), &module),
+        diagnostics,
+        components,
+        explanations,
+    })
+}
@@ -0,0 +1,82 @@
+use swc_core::common::{errors::HANDLER, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Replace the source text covered by `span`, an empty span inserts at that position
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A mechanical source-level fix for a diagnostic
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub description: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// A rule violation found while analyzing a component
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    pub fn error(rule: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            rule,
+            severity: Severity::Error,
+            span,
+            message: message.into(),
+            suggestion: None,
+            fix: None,
+        }
+    }
+
+    pub fn warning(rule: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            rule,
+            severity: Severity::Warning,
+            span,
+            message: message.into(),
+            suggestion: None,
+            fix: None,
+        }
+    }
+
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    /// Report the diagnostic through the SWC handler of the current compilation
+    pub fn emit(&self) {
+        let message = format!("[rask/{}] {}", self.rule, self.message);
+
+        HANDLER.with(|handler| {
+            let mut builder = match self.severity {
+                Severity::Error => handler.struct_span_err(self.span, &message),
+                Severity::Warning => handler.struct_span_warn(self.span, &message),
+            };
+            if let Some(suggestion) = &self.suggestion {
+                builder.help(suggestion);
+            }
+            builder.emit();
+        });
+    }
+}
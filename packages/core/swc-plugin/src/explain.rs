@@ -0,0 +1,46 @@
+use serde::Serialize;
+use swc_core::common::Span;
+
+use crate::location::Location;
+
+/// What the transform did with a candidate component function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Stateful,
+    Stateless,
+    Skipped,
+}
+
+impl Decision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Stateful => "stateful",
+            Decision::Stateless => "stateless",
+            Decision::Skipped => "skipped",
+        }
+    }
+}
+
+/// Why a candidate function was or wasn't compiled into a component
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub name: String,
+    pub decision: Decision,
+    pub reason: String,
+    #[serde(skip)]
+    pub span: Span,
+    pub location: Option<Location>,
+}
+
+pub const STATEFUL_REASON: &str = "returns an arrow function whose body reaches a VNode factory call";
+pub const STATELESS_REASON: &str = "returns a VNode factory call directly";
+pub const UNSUPPORTED_EXPORT_REASON: &str =
+    "export form not supported: `export const` components are not compiled, declare the component and export it separately";
+pub const FUNCTION_EXPRESSION_REASON: &str =
+    "function expressions assigned to a variable are not compiled, use a function declaration or an arrow function";
+
+/// Functions named like components are candidates even when they are not compiled
+pub fn is_candidate_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
@@ -0,0 +1,67 @@
+use swc_core::common::BytePos;
+
+use crate::diagnostics::{Diagnostic, Fix, TextEdit};
+
+/// Apply the fixes of the given diagnostics to the source text.
+/// A fix whose edits overlap an already accepted fix is skipped, so it can be picked up by a later run.
+/// Returns the fixed source together with the indices of the diagnostics whose fixes were applied.
+pub fn apply_fixes(source: &str, start_pos: BytePos, diagnostics: &[Diagnostic]) -> (String, Vec<usize>) {
+    let fixes: Vec<Option<&Fix>> = diagnostics.iter().map(|diagnostic| diagnostic.fix.as_ref()).collect();
+    apply_edits(source, start_pos, &fixes)
+}
+
+/// Apply fixes to the source text, skipping missing fixes and fixes that overlap an accepted one.
+/// Returns the fixed source together with the indices of the applied fixes.
+pub fn apply_edits<'a>(source: &str, start_pos: BytePos, fixes: &[Option<&'a Fix>]) -> (String, Vec<usize>) {
+    let mut accepted = vec![];
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    let mut edits: Vec<(usize, usize, &'a TextEdit)> = vec![];
+
+    for (index, fix) in fixes.iter().enumerate() {
+        let Some(fix) = fix else {
+            continue;
+        };
+
+        let fix_ranges: Option<Vec<(usize, usize)>> = fix
+            .edits
+            .iter()
+            .map(|edit| edit_range(source, start_pos, edit))
+            .collect();
+        let Some(fix_ranges) = fix_ranges else {
+            continue;
+        };
+
+        let overlaps = fix_ranges
+            .iter()
+            .any(|&(lo, hi)| ranges.iter().any(|&(other_lo, other_hi)| lo < other_hi && other_lo < hi));
+        if overlaps {
+            continue;
+        }
+
+        for (&(lo, hi), edit) in fix_ranges.iter().zip(&fix.edits) {
+            edits.push((lo, hi, edit));
+        }
+        ranges.extend(fix_ranges);
+        accepted.push(index);
+    }
+
+    // Apply back to front so earlier offsets stay valid, insertions at the same offset keep their order
+    edits.sort_by_key(|&(lo, hi, _)| (lo, hi));
+    let mut fixed = source.to_string();
+    for (lo, hi, edit) in edits.into_iter().rev() {
+        fixed.replace_range(lo..hi, &edit.replacement);
+    }
+
+    (fixed, accepted)
+}
+
+/// Byte range of an edit within the source, `None` if it falls outside of it
+fn edit_range(source: &str, start_pos: BytePos, edit: &TextEdit) -> Option<(usize, usize)> {
+    if edit.span.lo < start_pos || edit.span.hi < edit.span.lo {
+        return None;
+    }
+    let lo = (edit.span.lo - start_pos).0 as usize;
+    let hi = (edit.span.hi - start_pos).0 as usize;
+
+    (hi <= source.len() && source.is_char_boundary(lo) && source.is_char_boundary(hi)).then_some((lo, hi))
+}
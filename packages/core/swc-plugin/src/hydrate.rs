@@ -0,0 +1,294 @@
+use serde_json::Value;
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::{private_ident, quote_ident},
+    visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use crate::ssr::{arg, factory_name, inner_html, is_empty, str_value, VOID_ELEMENTS};
+
+/// The static structure a component renders, as the JSON `registerHydration` expects.
+/// Elements are `[tag, ...children]`, static text a string and markup only known at runtime `0`,
+/// matching the regions the `ssr` target brackets with markers when hydrating.
+/// `None` when the render output is not a single VNode factory call.
+pub fn structure(func: &Function, is_stateful: bool) -> Option<String> {
+    let body = func.body.as_ref()?;
+    let render = if is_stateful {
+        body.stmts.iter().find_map(|stmt| match stmt {
+            Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => match &**arg {
+                Expr::Arrow(arrow) => match &*arrow.body {
+                    BlockStmtOrExpr::Expr(expr) => Some(&**expr),
+                    BlockStmtOrExpr::BlockStmt(block) => single_return(block),
+                },
+                _ => None,
+            },
+            _ => None,
+        })?
+    } else {
+        single_return(body)?
+    };
+
+    let Expr::Call(call) = unwrap_paren(render) else {
+        return None;
+    };
+    factory_name(call)?;
+
+    let mut nodes = vec![];
+    children(render, &mut nodes);
+    serde_json::to_string(&nodes).ok()
+}
+
+fn unwrap_paren(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_paren(&paren.expr),
+        _ => expr,
+    }
+}
+
+/// The argument of the only return statement of a block, ignoring nested functions
+fn single_return(block: &BlockStmt) -> Option<&Expr> {
+    let mut counter = ReturnCounter { count: 0 };
+    block.visit_with(&mut counter);
+    if counter.count != 1 {
+        return None;
+    }
+    block.stmts.iter().find_map(|stmt| match stmt {
+        Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => Some(&**arg),
+        _ => None,
+    })
+}
+
+struct ReturnCounter {
+    count: usize,
+}
+
+impl Visit for ReturnCounter {
+    noop_visit_type!();
+
+    fn visit_return_stmt(&mut self, _: &ReturnStmt) {
+        self.count += 1;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// Adjacent static text ends up as one text node in the server markup
+fn push_text(text: String, nodes: &mut Vec<Value>) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(Value::String(previous)) = nodes.last_mut() {
+        previous.push_str(&text);
+        return;
+    }
+    nodes.push(Value::String(text));
+}
+
+fn children(expr: &Expr, nodes: &mut Vec<Value>) {
+    match expr {
+        Expr::Paren(paren) => children(&paren.expr, nodes),
+        Expr::Call(call) if factory_name(call).is_some() => match factory_name(call) {
+            Some("createVNode") => match arg(call, 1) {
+                Some(Expr::Lit(Lit::Str(tag))) => {
+                    let tag = str_value(tag);
+                    let mut element = vec![Value::String(tag.clone())];
+                    if !VOID_ELEMENTS.iter().any(|void| *void == tag) {
+                        // Inner HTML is only known at runtime and replaces the children
+                        if inner_html(arg(call, 5)).is_some() {
+                            element.push(Value::from(0));
+                        } else if let Some(content) = arg(call, 3) {
+                            children(content, &mut element);
+                        }
+                    }
+                    nodes.push(Value::Array(element));
+                }
+                _ => nodes.push(Value::from(0)),
+            },
+            Some("createComponentVNode") => nodes.push(Value::from(0)),
+            _ => {
+                if let Some(content) = arg(call, 0) {
+                    children(content, nodes);
+                }
+            }
+        },
+        Expr::Lit(Lit::Str(str)) => push_text(str_value(str), nodes),
+        Expr::Lit(Lit::Num(num)) => push_text(num.value.to_string(), nodes),
+        Expr::Lit(Lit::Bool(_)) => {}
+        Expr::Array(array) if array.elems.iter().flatten().all(|elem| elem.spread.is_none()) => {
+            for elem in array.elems.iter().flatten() {
+                children(&elem.expr, nodes);
+            }
+        }
+        _ if is_empty(expr) => {}
+        _ => nodes.push(Value::from(0)),
+    }
+}
+
+/// Register the structures of the module's components for the development mismatch check of `hydrate`
+pub fn register_structures(module: &mut Module, structures: &[(String, String)], import_source: &str) {
+    if structures.is_empty() {
+        return;
+    }
+    let register = private_ident!("registerHydration");
+
+    module.body.insert(
+        0,
+        ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: register.clone(),
+                imported: Some(ModuleExportName::Ident(quote_ident!("registerHydration").into())),
+                is_type_only: false,
+            })],
+            src: Box::new(string(import_source)),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        })),
+    );
+
+    // `import.meta.env.DEV && registerHydration(...)` lets production builds drop the structures
+    let is_dev = Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::MetaProp(MetaPropExpr {
+                span: DUMMY_SP,
+                kind: MetaPropKind::ImportMeta,
+            })),
+            prop: MemberProp::Ident(quote_ident!("env")),
+        })),
+        prop: MemberProp::Ident(quote_ident!("DEV")),
+    });
+
+    for (name, structure) in structures {
+        let call = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(Expr::Ident(register.clone()))),
+            args: vec![
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(string(name)))),
+                },
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(string(structure)))),
+                },
+            ],
+            type_args: None,
+        });
+        module.body.push(ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: BinaryOp::LogicalAnd,
+                left: Box::new(is_dev.clone()),
+                right: Box::new(call),
+            })),
+        })));
+    }
+}
+
+/// Set the hydration key of the `ssr` target's components, which `ssrComponent` marks their boundaries with,
+/// right after their declarations
+pub fn key_components(module: &mut Module, keys: &[(Id, String)]) {
+    if keys.is_empty() {
+        return;
+    }
+    module.visit_mut_with(&mut ComponentKeys { keys });
+}
+
+struct ComponentKeys<'a> {
+    keys: &'a [(Id, String)],
+}
+
+impl ComponentKeys<'_> {
+    /// `Name.hydrationKey = "<key>"` for a declared component
+    fn assignment(&self, ident: &Ident) -> Option<Stmt> {
+        let (_, key) = self.keys.iter().find(|(id, _)| *id == ident.to_id())?;
+        Some(Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Assign(AssignExpr {
+                span: DUMMY_SP,
+                op: AssignOp::Assign,
+                left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(ident.clone())),
+                    prop: MemberProp::Ident(quote_ident!("hydrationKey")),
+                })),
+                right: Box::new(Expr::Lit(Lit::Str(string(key)))),
+            })),
+        }))
+    }
+}
+
+fn declared_ident(decl: &Decl) -> Option<&Ident> {
+    match decl {
+        Decl::Fn(fn_decl) => Some(&fn_decl.ident),
+        Decl::Var(var) => match var.decls.as_slice() {
+            [VarDeclarator {
+                name: Pat::Ident(binding),
+                ..
+            }] => Some(&binding.id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl VisitMut for ComponentKeys<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+
+        let mut index = 0;
+        while index < items.len() {
+            let ident = match &items[index] {
+                ModuleItem::Stmt(Stmt::Decl(decl))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => declared_ident(decl),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                    decl: DefaultDecl::Fn(FnExpr { ident, .. }),
+                    ..
+                })) => ident.as_ref(),
+                _ => None,
+            };
+            if let Some(assignment) = ident.and_then(|ident| self.assignment(ident)) {
+                index += 1;
+                items.insert(index, ModuleItem::Stmt(assignment));
+            }
+            index += 1;
+        }
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+
+        let mut index = 0;
+        while index < stmts.len() {
+            let ident = match &stmts[index] {
+                Stmt::Decl(decl) => declared_ident(decl),
+                _ => None,
+            };
+            if let Some(assignment) = ident.and_then(|ident| self.assignment(ident)) {
+                index += 1;
+                stmts.insert(index, assignment);
+            }
+            index += 1;
+        }
+    }
+}
+
+fn string(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: Wtf8Atom::from(value),
+        raw: None,
+    }
+}
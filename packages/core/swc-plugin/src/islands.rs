@@ -0,0 +1,399 @@
+use serde::Serialize;
+use swc_core::common::{BytePos, Span, DUMMY_SP};
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::quote_ident,
+    visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use crate::analysis::callee_name;
+use crate::lazy::LazyManifest;
+use crate::location::Location;
+
+/// The `client:*` directives and the strategy the island is hydrated with
+const DIRECTIVES: &[(&str, &str)] = &[
+    ("client:load", "load"),
+    ("client:visible", "visible"),
+    ("client:idle", "idle"),
+];
+
+/// Name of the generated export mapping island components to their loaders
+pub const LOADERS_EXPORT: &str = "__raskIslands";
+
+/// A component usage marked with a `client:*` directive
+#[derive(Debug, Clone, Serialize)]
+pub struct IslandManifest {
+    pub component: String,
+    pub strategy: &'static str,
+    /// Module the component is imported from, `None` when it is declared in the same module
+    pub source: Option<String>,
+    /// Export the component is imported as
+    pub export: Option<String>,
+    #[serde(skip)]
+    pub span: Span,
+    pub location: Option<Location>,
+    /// The binding the component is used through
+    #[serde(skip)]
+    pub local: Ident,
+}
+
+/// The strategy and index of the `client:*` prop of a component VNode's props, as emitted by Inferno's JSX transform
+pub fn directive(props: &ObjectLit) -> Option<(&'static str, usize)> {
+    props.props.iter().enumerate().find_map(|(index, prop)| {
+        let PropOrSpread::Prop(prop) = prop else {
+            return None;
+        };
+        let Prop::KeyValue(KeyValueProp { key: PropName::Str(key), .. }) = &**prop else {
+            return None;
+        };
+        DIRECTIVES
+            .iter()
+            .find(|(name, _)| &*key.value == *name)
+            .map(|(_, strategy)| (*strategy, index))
+    })
+}
+
+/// Find the components used as islands. Directives are removed for the client, the `ssr` target renders them as boundaries.
+/// Islands whose import was compiled to `lazy` are loaded from the lazy component's module.
+pub fn collect(module: &mut Module, strip: bool, lazy: &[LazyManifest]) -> Vec<IslandManifest> {
+    let mut collector = IslandCollector {
+        islands: vec![],
+        strip,
+    };
+    module.visit_mut_with(&mut collector);
+
+    for island in &mut collector.islands {
+        if let Some((source, export)) = import_of(module, &island.component) {
+            island.source = Some(source);
+            island.export = Some(export);
+        } else if let Some(lazy) = lazy.iter().find(|lazy| lazy.component == island.component) {
+            island.source = Some(lazy.source.clone());
+            island.export = Some(lazy.export.clone());
+        }
+    }
+    collector.islands
+}
+
+struct IslandCollector {
+    islands: Vec<IslandManifest>,
+    strip: bool,
+}
+
+impl IslandCollector {
+    fn add(&mut self, component: &Ident, strategy: &'static str, span: Span) {
+        if self
+            .islands
+            .iter()
+            .any(|island| island.component == *component.sym && island.strategy == strategy)
+        {
+            return;
+        }
+        self.islands.push(IslandManifest {
+            component: component.sym.to_string(),
+            strategy,
+            source: None,
+            export: None,
+            span,
+            location: None,
+            local: component.clone(),
+        });
+    }
+}
+
+impl VisitMut for IslandCollector {
+    noop_visit_mut_type!();
+
+    // Untransformed JSX, as parsed by the native tools
+    fn visit_mut_jsx_opening_element(&mut self, element: &mut JSXOpeningElement) {
+        element.visit_mut_children_with(self);
+
+        let JSXElementName::Ident(component) = &element.name else {
+            return;
+        };
+        if !component.sym.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return;
+        }
+        let found = element.attrs.iter().enumerate().find_map(|(index, attr)| {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(name),
+                ..
+            }) = attr
+            else {
+                return None;
+            };
+            let directive = format!("{}:{}", name.ns.sym, name.name.sym);
+            DIRECTIVES
+                .iter()
+                .find(|(name, _)| *name == directive)
+                .map(|(_, strategy)| (*strategy, index))
+        });
+        let Some((strategy, index)) = found else {
+            return;
+        };
+
+        let component = component.clone();
+        self.add(&component, strategy, element.span);
+        if self.strip {
+            element.attrs.remove(index);
+        }
+    }
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.visit_mut_children_with(self);
+
+        if callee_name(call) != Some("createComponentVNode") {
+            return;
+        }
+        if call.args.len() < 3 {
+            return;
+        }
+        let Expr::Ident(component) = &*call.args[1].expr else {
+            return;
+        };
+        let component = component.clone();
+        let Expr::Object(props) = &mut *call.args[2].expr else {
+            return;
+        };
+        let Some((strategy, index)) = directive(props) else {
+            return;
+        };
+
+        self.add(&component, strategy, call.span);
+        if self.strip {
+            props.props.remove(index);
+        }
+    }
+}
+
+/// Start positions of the imports whose components are only used as islands. The client compiles them like
+/// `/* @rask-lazy */` imports, so the static import does not pull the island's chunk into the page.
+pub fn island_imports(module: &Module) -> Vec<BytePos> {
+    let mut uses = UseCounter::default();
+    module.visit_with(&mut uses);
+
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => Some(import),
+            _ => None,
+        })
+        .filter(|import| {
+            let mut locals = vec![];
+            for specifier in &import.specifiers {
+                match specifier {
+                    ImportSpecifier::Named(named) if named.is_type_only => {}
+                    ImportSpecifier::Named(named) => locals.push(named.local.to_id()),
+                    ImportSpecifier::Default(default) => locals.push(default.local.to_id()),
+                    // A namespace is not a component
+                    ImportSpecifier::Namespace(_) => return false,
+                }
+            }
+            // Every binding of the import has to be an island, anything else still needs the module
+            !locals.is_empty()
+                && locals
+                    .iter()
+                    .all(|local| uses.islands.contains(local) && !uses.other.contains(local))
+        })
+        .map(|import| import.span.lo)
+        .collect()
+}
+
+/// Bindings used as island components, and bindings used in any other way
+#[derive(Default)]
+struct UseCounter {
+    islands: Vec<Id>,
+    other: Vec<Id>,
+}
+
+impl Visit for UseCounter {
+    noop_visit_type!();
+
+    // The import's own bindings are not uses
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.other.push(ident.to_id());
+    }
+
+    fn visit_jsx_opening_element(&mut self, element: &JSXOpeningElement) {
+        let is_island = element.attrs.iter().any(|attr| {
+            let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(name),
+                ..
+            }) = attr
+            else {
+                return false;
+            };
+            let directive = format!("{}:{}", name.ns.sym, name.name.sym);
+            DIRECTIVES.iter().any(|(name, _)| *name == directive)
+        });
+        match &element.name {
+            JSXElementName::Ident(component) if is_island => self.islands.push(component.to_id()),
+            name => name.visit_with(self),
+        }
+        element.attrs.visit_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if callee_name(call) == Some("createComponentVNode") && call.args.len() >= 3 {
+            if let (Expr::Ident(component), Expr::Object(props)) = (&*call.args[1].expr, &*call.args[2].expr) {
+                if directive(props).is_some() {
+                    self.islands.push(component.to_id());
+                    call.callee.visit_with(self);
+                    for (index, arg) in call.args.iter().enumerate() {
+                        if index != 1 {
+                            arg.visit_with(self);
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// The module specifier and export name a local binding is imported from
+fn import_of(module: &Module, local: &str) -> Option<(String, String)> {
+    module.body.iter().find_map(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return None;
+        };
+        import.specifiers.iter().find_map(|specifier| {
+            let export = match specifier {
+                ImportSpecifier::Named(named) if &*named.local.sym == local => match &named.imported {
+                    Some(ModuleExportName::Ident(imported)) => imported.sym.to_string(),
+                    Some(ModuleExportName::Str(imported)) => imported.value.to_string_lossy().into_owned(),
+                    None => local.to_string(),
+                },
+                ImportSpecifier::Default(default) if &*default.local.sym == local => "default".to_string(),
+                _ => return None,
+            };
+            Some((import.src.value.to_string_lossy().into_owned(), export))
+        })
+    })
+}
+
+/// Export loaders for the island components, `import("./Counter").then((module) => module.Counter)`.
+/// The dynamic imports let bundlers split every island into its own chunk for `bootIslands`, the client has no static
+/// import of them left since [`island_imports`] are compiled to `lazy`.
+pub fn append_loaders(module: &mut Module, islands: &[IslandManifest]) {
+    if islands.is_empty() {
+        return;
+    }
+    let mut props: Vec<PropOrSpread> = vec![];
+    let mut loaded_components: Vec<&str> = vec![];
+
+    for island in islands {
+        // A component used with several strategies is loaded the same way
+        if loaded_components.contains(&island.component.as_str()) {
+            continue;
+        }
+        loaded_components.push(&island.component);
+
+        let load = match (&island.source, &island.export) {
+            (Some(source), Some(export)) => {
+                let loaded = quote_ident!("module");
+                let import = Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    ctxt: Default::default(),
+                    callee: Callee::Import(Import {
+                        span: DUMMY_SP,
+                        phase: Default::default(),
+                    }),
+                    args: vec![ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(Expr::Lit(Lit::Str(string(source)))),
+                    }],
+                    type_args: None,
+                });
+                call(
+                    Expr::Member(MemberExpr {
+                        span: DUMMY_SP,
+                        obj: Box::new(import),
+                        prop: MemberProp::Ident(quote_ident!("then")),
+                    }),
+                    vec![arrow(
+                        vec![Pat::Ident(Ident::from(loaded.clone()).into())],
+                        Expr::Member(MemberExpr {
+                            span: DUMMY_SP,
+                            obj: Box::new(Expr::Ident(loaded.into())),
+                            prop: MemberProp::Computed(ComputedPropName {
+                                span: DUMMY_SP,
+                                expr: Box::new(Expr::Lit(Lit::Str(string(export)))),
+                            }),
+                        }),
+                    )],
+                )
+            }
+            // Declared in this module, so it is part of the module's own chunk
+            _ => call(
+                Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: Box::new(Expr::Ident(quote_ident!("Promise").into())),
+                    prop: MemberProp::Ident(quote_ident!("resolve")),
+                }),
+                vec![Expr::Ident(island.local.clone())],
+            ),
+        };
+        props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Str(string(&island.component)),
+            value: Box::new(arrow(vec![], load)),
+        }))));
+    }
+
+    module.body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span: DUMMY_SP,
+        decl: Decl::Var(Box::new(VarDecl {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(Ident::from(quote_ident!(LOADERS_EXPORT)).into()),
+                init: Some(Box::new(Expr::Object(ObjectLit { span: DUMMY_SP, props }))),
+                definite: false,
+            }],
+        })),
+    })));
+}
+
+fn call(callee: Expr, args: Vec<Expr>) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(Box::new(callee)),
+        args: args
+            .into_iter()
+            .map(|expr| ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+    })
+}
+
+fn arrow(params: Vec<Pat>, body: Expr) -> Expr {
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params,
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(body))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+fn string(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: Wtf8Atom::from(value),
+        raw: None,
+    }
+}
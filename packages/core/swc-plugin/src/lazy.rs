@@ -0,0 +1,274 @@
+use serde::Serialize;
+use swc_core::common::{comments::Comments, BytePos, Span, DUMMY_SP};
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::{private_ident, quote_ident},
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::callee_name;
+use crate::location::Location;
+
+/// Marks an import of a component that should be loaded in its own chunk, `/* @rask-lazy */ import Settings from "./Settings"`
+const ANNOTATION: &str = "@rask-lazy";
+
+/// A component loaded with a dynamic import the first time it renders
+#[derive(Debug, Clone, Serialize)]
+pub struct LazyManifest {
+    pub component: String,
+    /// Module split into its own chunk, which can be preloaded before the component renders
+    pub source: String,
+    /// Export of the module the component is
+    pub export: String,
+    #[serde(skip)]
+    pub span: Span,
+    pub location: Option<Location>,
+}
+
+/// Start positions of the imports annotated with `/* @rask-lazy */`, read before the transform runs
+pub fn annotated_imports<C: Comments>(module: &Module, comments: &C) -> Vec<BytePos> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(import.span.lo),
+            _ => None,
+        })
+        .filter(|pos| {
+            comments
+                .get_leading(*pos)
+                .is_some_and(|leading| leading.iter().any(|comment| comment.text.trim() == ANNOTATION))
+        })
+        .collect()
+}
+
+/// Replace the annotated imports with `lazy` components and describe every lazy component of the module
+pub fn compile_module(module: &mut Module, annotated: &[BytePos], import_source: &str) -> Vec<LazyManifest> {
+    let mut lazy_components = vec![];
+    let mut lazy_ident = None;
+
+    for item in &mut module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        if import.type_only || !annotated.contains(&import.span.lo) {
+            continue;
+        }
+        let lazy = lazy_ident.get_or_insert_with(|| private_ident!("lazy")).clone();
+        let source = import.src.value.to_string_lossy().into_owned();
+
+        let mut decls = vec![];
+        for specifier in &import.specifiers {
+            let (local, export) = match specifier {
+                ImportSpecifier::Default(default) => (default.local.clone(), "default".to_string()),
+                ImportSpecifier::Named(named) if !named.is_type_only => {
+                    let export = match &named.imported {
+                        Some(ModuleExportName::Ident(imported)) => imported.sym.to_string(),
+                        Some(ModuleExportName::Str(imported)) => imported.value.to_string_lossy().into_owned(),
+                        None => named.local.sym.to_string(),
+                    };
+                    (named.local.clone(), export)
+                }
+                // A namespace is not a component
+                _ => continue,
+            };
+
+            lazy_components.push(LazyManifest {
+                component: local.sym.to_string(),
+                source: source.clone(),
+                export: export.clone(),
+                span: import.span,
+                location: None,
+            });
+            decls.push(VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(local.into()),
+                init: Some(Box::new(lazy_call(&lazy, &source, &export))),
+                definite: false,
+            });
+        }
+
+        if decls.is_empty() {
+            continue;
+        }
+        let span = import.span;
+        *item = ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            span,
+            ctxt: Default::default(),
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls,
+        }))));
+    }
+
+    let mut collector = LazyCollector {
+        lazy: lazy_bindings(module, import_source),
+        components: &mut lazy_components,
+    };
+    module.visit_with(&mut collector);
+
+    if let Some(lazy) = lazy_ident {
+        module.body.insert(
+            0,
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: lazy,
+                    imported: Some(ModuleExportName::Ident(quote_ident!("lazy").into())),
+                    is_type_only: false,
+                })],
+                src: Box::new(string(import_source)),
+                type_only: false,
+                with: None,
+                phase: Default::default(),
+            })),
+        );
+    }
+
+    lazy_components
+}
+
+/// `lazy(() => import("<source>"), "<export>")`
+fn lazy_call(lazy: &Ident, source: &str, export: &str) -> Expr {
+    let import = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Import(Import {
+            span: DUMMY_SP,
+            phase: Default::default(),
+        }),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Lit(Lit::Str(string(source)))),
+        }],
+        type_args: None,
+    });
+    let load = Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params: vec![],
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(import))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    });
+
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        callee: Callee::Expr(Box::new(Expr::Ident(lazy.clone()))),
+        args: vec![
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(load),
+            },
+            ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Lit(Lit::Str(string(export)))),
+            },
+        ],
+        type_args: None,
+    })
+}
+
+/// Local names `lazy` is imported as from the import source
+fn lazy_bindings(module: &Module, import_source: &str) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if &*import.src.value == import_source => Some(import),
+            _ => None,
+        })
+        .flat_map(|import| &import.specifiers)
+        .filter_map(|specifier| match specifier {
+            ImportSpecifier::Named(named) => {
+                let imported = match &named.imported {
+                    Some(ModuleExportName::Ident(imported)) => &*imported.sym,
+                    Some(ModuleExportName::Str(_)) => return None,
+                    None => &*named.local.sym,
+                };
+                (imported == "lazy").then(|| named.local.sym.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds `const Settings = lazy(() => import("./Settings"))`, with an optional export name
+struct LazyCollector<'a> {
+    lazy: Vec<String>,
+    components: &'a mut Vec<LazyManifest>,
+}
+
+impl Visit for LazyCollector<'_> {
+    noop_visit_type!();
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        declarator.visit_children_with(self);
+
+        let (Pat::Ident(name), Some(init)) = (&declarator.name, &declarator.init) else {
+            return;
+        };
+        let Expr::Call(call) = &**init else {
+            return;
+        };
+        if !callee_name(call).is_some_and(|callee| self.lazy.iter().any(|lazy| lazy == callee)) {
+            return;
+        }
+        let Some(source) = call.args.first().and_then(|load| dynamic_import_source(&load.expr)) else {
+            return;
+        };
+        let export = match call.args.get(1).map(|export| &*export.expr) {
+            Some(Expr::Lit(Lit::Str(export))) => export.value.to_string_lossy().into_owned(),
+            None => "default".to_string(),
+            // Only known at runtime
+            Some(_) => return,
+        };
+
+        self.components.push(LazyManifest {
+            component: name.id.sym.to_string(),
+            source,
+            export,
+            span: declarator.span,
+            location: None,
+        });
+    }
+}
+
+/// The module loaded by `() => import("<source>")`
+fn dynamic_import_source(load: &Expr) -> Option<String> {
+    let Expr::Arrow(arrow) = load else {
+        return None;
+    };
+    let body = match &*arrow.body {
+        BlockStmtOrExpr::Expr(expr) => &**expr,
+        BlockStmtOrExpr::BlockStmt(block) => match block.stmts.as_slice() {
+            [Stmt::Return(ReturnStmt { arg: Some(arg), .. })] => &**arg,
+            _ => return None,
+        },
+    };
+    let Expr::Call(CallExpr {
+        callee: Callee::Import(_),
+        args,
+        ..
+    }) = body
+    else {
+        return None;
+    };
+    match args.first().map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(source))) => Some(source.value.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+fn string(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: Wtf8Atom::from(value),
+        raw: None,
+    }
+}
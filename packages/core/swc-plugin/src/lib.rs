@@ -1,31 +1,471 @@
+pub mod analysis;
+pub mod async_setup;
+pub mod bindings;
+#[cfg(feature = "native")]
+pub mod codemod;
+#[cfg(feature = "native")]
+pub mod compile;
+pub mod diagnostics;
+pub mod explain;
+pub mod fix;
+pub mod hydrate;
+pub mod islands;
+pub mod lazy;
+#[cfg(feature = "native")]
+pub mod lint;
+pub mod lists;
+pub mod location;
+pub mod manifest;
+#[cfg(feature = "playground")]
+pub mod playground;
+pub mod refactor;
+#[cfg(feature = "cli")]
+pub mod report;
+pub mod routes;
+pub mod rules;
+pub mod server_actions;
+pub mod ssr;
+
+#[cfg(feature = "native")]
+pub use compile::{transform_source, Diagnostics, Output};
+use diagnostics::Diagnostic;
+use explain::{Decision, Explanation};
+use islands::IslandManifest;
+use lazy::LazyManifest;
+use location::Location;
+use manifest::{ComponentKind, ComponentManifest, ModuleManifest, MANIFEST_VERSION};
+use routes::RouteManifest;
+use swc_core::common::{
+    comments::{Comment, CommentKind, Comments},
+    errors::{SourceMapper, HANDLER},
+    BytePos, Span, Spanned,
+};
 use swc_core::ecma::{
     ast::*,
     atoms::Wtf8Atom,
     utils::{private_ident, quote_ident},
     visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
 };
-use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
+#[cfg(not(feature = "playground"))]
+use swc_core::plugin::{
+    metadata::TransformPluginMetadataContextKind, plugin_transform, proxies::TransformPluginProgramMetadata,
+};
 
 #[derive(Default, Clone, serde::Deserialize)]
 #[serde(crate = "serde")]
 pub struct Config {
     #[serde(default, rename = "importSource")]
     pub import_source: Option<String>,
+    #[serde(default, rename = "nestedComponents")]
+    pub nested_components: NestedComponents,
+    /// Attach a JSON manifest describing the module's components as a leading comment
+    #[serde(default)]
+    pub manifest: bool,
+    /// Report why each candidate function was or wasn't compiled
+    #[serde(default)]
+    pub explain: bool,
+    /// Annotate candidate functions in the output with `/* rask: <decision> */` comments
+    #[serde(default, rename = "explainComments")]
+    pub explain_comments: bool,
+    /// What the compiled components render to
+    #[serde(default)]
+    pub target: Target,
+    /// Emit hydration markers for the `ssr` target, and the structures `hydrate` checks them against for the client
+    #[serde(default)]
+    pub hydrate: bool,
+    /// Pass a call-site ID to the state hooks called in setup, so the runtime can snapshot and restore their state
+    #[serde(default, rename = "hookIds")]
+    pub hook_ids: bool,
+    /// Compile "use server" functions to RPC stubs for the client and an action registry for the `ssr` target
+    #[serde(default, rename = "serverActions")]
+    pub server_actions: bool,
+    /// Compile the reactive text children and attributes of render closures into bindings updating the DOM directly
+    #[serde(default)]
+    pub bindings: bool,
+    /// Compile `.map` calls of render closures rendering an element per item into lists rendering each item on its own
+    #[serde(default)]
+    pub lists: bool,
+}
+
+/// The environment components are compiled for
+#[derive(Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(crate = "serde", rename_all = "camelCase")]
+pub enum Target {
+    /// Component classes rendering VNodes in the browser
+    #[default]
+    Client,
+    /// Plain component functions rendering HTML strings, see `renderToString` in the runtime
+    Ssr,
+}
+
+/// Which components declared inside other functions get compiled
+#[derive(Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(crate = "serde", rename_all = "camelCase")]
+pub enum NestedComponents {
+    /// Only compile components declared at module level
+    Module,
+    /// Also compile components declared in a component's setup, but not in its render function
+    Setup,
+    /// Compile components wherever they are declared
+    #[default]
+    All,
+}
+
+impl NestedComponents {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NestedComponents::Module => "module",
+            NestedComponents::Setup => "setup",
+            NestedComponents::All => "all",
+        }
+    }
+}
+
+/// The kind of function body the visitor is currently inside
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Function,
+    Setup,
+    Render,
 }
 
 pub struct RaskComponentTransform {
     config: Config,
     import_rask_stateful_component: Option<Ident>,
     import_rask_stateless_component: Option<Ident>,
+    import_use_async_setup: Option<Ident>,
+    binding_helpers: bindings::Helpers,
+    import_map_list: Option<Ident>,
+    diagnostics: Vec<Diagnostic>,
+    components: Vec<ComponentManifest>,
+    explanations: Vec<Explanation>,
+    scopes: Vec<Scope>,
+    /// Component hydration keys with the static structure they render, see [`hydrate::structure`]
+    hydration: Vec<(String, String)>,
+    /// The `ssr` target's components with the hydration key their boundary is marked with
+    hydration_keys: Vec<(Id, String)>,
+    /// The file being transformed, part of the hook call-site IDs
+    file: Option<String>,
+    /// The components whose setup is being visited, with the ordinal of their next hook
+    hook_ordinals: Vec<(String, usize)>,
+    /// Local bindings of the [`ID_HOOKS`] imported from the import source, including aliases
+    hook_imports: Vec<Id>,
+    islands: Vec<IslandManifest>,
+    /// Start positions of the imports annotated with `/* @rask-lazy */`
+    lazy_imports: Vec<BytePos>,
+    lazy: Vec<LazyManifest>,
+    routes: Vec<RouteManifest>,
+}
+
+/// Hooks whose state can be snapshotted and restored, given a call-site ID as their last argument
+const ID_HOOKS: &[&str] = &["useState", "useAsync", "useAction", "useDerived"];
+
+/// Short FNV-1a hash of a file name, so IDs are stable without exposing the file's path
+fn file_hash(file: &str) -> String {
+    let hash = file
+        .bytes()
+        .fold(0x811c9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+    format!("{:08x}", hash)
+}
+
+/// The local bindings of the [`ID_HOOKS`] imported from the import source
+fn hook_imports(module: &Module, import_source: &str) -> Vec<Id> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                if &*import.src.value == import_source && !import.type_only =>
+            {
+                Some(&import.specifiers)
+            }
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|specifier| match specifier {
+            ImportSpecifier::Named(named) if !named.is_type_only => {
+                let imported = match &named.imported {
+                    Some(ModuleExportName::Ident(ident)) => &*ident.sym,
+                    Some(ModuleExportName::Str(_)) => return None,
+                    None => &*named.local.sym,
+                };
+                ID_HOOKS.contains(&imported).then(|| named.local.to_id())
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 impl RaskComponentTransform {
-    fn new(config: Config) -> Self {
+    /// Create the transform, run it with `visit_mut_with` or use [`rask_transform`] to get a pass
+    pub fn new(config: Config) -> Self {
         RaskComponentTransform {
             config,
             import_rask_stateful_component: None,
             import_rask_stateless_component: None,
+            import_use_async_setup: None,
+            binding_helpers: Default::default(),
+            import_map_list: None,
+            diagnostics: vec![],
+            components: vec![],
+            explanations: vec![],
+            scopes: vec![],
+            hydration: vec![],
+            hydration_keys: vec![],
+            file: None,
+            hook_ordinals: vec![],
+            hook_imports: vec![],
+            islands: vec![],
+            lazy_imports: vec![],
+            lazy: vec![],
+            routes: vec![],
+        }
+    }
+
+    /// Set the name of the transformed file, relative to the project so IDs match across machines
+    pub fn with_file(mut self, file: Option<String>) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Set the imports to load lazily, found with [`lazy::annotated_imports`] since the transform has no access to comments
+    pub fn with_lazy_imports(mut self, lazy_imports: Vec<BytePos>) -> Self {
+        self.lazy_imports = lazy_imports;
+        self
+    }
+
+    /// Classify a function the way the transform does, `None` when it is not compiled as a component
+    pub fn classify(func: &Function) -> Option<ComponentKind> {
+        let transform = Self::new(Config::default());
+        if transform.is_rask_component(func) {
+            Some(ComponentKind::Stateful)
+        } else if transform.is_stateless_component(func) {
+            Some(ComponentKind::Stateless)
+        } else {
+            None
+        }
+    }
+
+    /// Convert an arrow function component to a function, keeping the spans of its body
+    pub fn arrow_component_function(arrow: &ArrowExpr) -> Function {
+        Self::new(Config::default()).arrow_to_function(arrow)
+    }
+
+    /// Decide whether a component declared in the current scope should be compiled.
+    /// Components declared during render are recreated on every render, so they are always reported.
+    fn allow_nested_component(&mut self, name: &Ident) -> bool {
+        let in_render = self.scopes.contains(&Scope::Render);
+        if in_render {
+            self.diagnostics.push(rules::nested_component::diagnostic(name));
+        }
+
+        let allowed = match self.config.nested_components {
+            NestedComponents::Module => self.scopes.is_empty(),
+            NestedComponents::Setup => !in_render,
+            NestedComponents::All => true,
+        };
+        if !allowed {
+            let reason = format!(
+                "declared in a nested scope that the `nestedComponents: \"{}\"` policy does not compile",
+                self.config.nested_components.as_str()
+            );
+            self.explain(name.sym.as_ref(), name.span, Decision::Skipped, reason);
+        }
+        allowed
+    }
+
+    /// Record the decision made for a candidate function
+    fn explain(&mut self, name: &str, span: Span, decision: Decision, reason: impl Into<String>) {
+        self.explanations.push(Explanation {
+            name: name.to_string(),
+            decision,
+            reason: reason.into(),
+            span,
+            location: None,
+        });
+    }
+
+    /// Record why a function that looks like a component was not compiled
+    fn explain_skipped(&mut self, name: &str, func: &Function) {
+        if explain::is_candidate_name(name) {
+            let reason = self.skip_reason(func);
+            self.explain(name, func.span, Decision::Skipped, reason);
+        }
+    }
+
+    /// Explain why neither `is_rask_component` nor `is_stateless_component` matched
+    fn skip_reason(&self, func: &Function) -> &'static str {
+        if func.is_generator {
+            return "generator functions are not compiled";
+        }
+        let Some(body) = &func.body else {
+            return "function has no body";
+        };
+
+        let returns: Vec<&Expr> = body
+            .stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => Some(&**arg),
+                _ => None,
+            })
+            .collect();
+
+        if returns.is_empty() {
+            "no top-level return statement with a value"
+        } else if returns.iter().any(|ret| matches!(ret, Expr::Arrow(_))) {
+            "returns an arrow function, but no VNode factory call is reachable from its body"
+        } else {
+            "return is not an arrow and no VNode factory call is reachable"
+        }
+    }
+
+    /// Visit a component body, tracking which statements belong to setup and which to render
+    fn visit_component_body(&mut self, name: &Ident, func: &mut Function, is_stateful: bool) {
+        if !is_stateful {
+            self.scopes.push(Scope::Render);
+            func.body.visit_mut_with(self);
+            self.scopes.pop();
+            return;
+        }
+
+        self.hook_ordinals.push((name.sym.to_string(), 0));
+        self.scopes.push(Scope::Setup);
+        if let Some(body) = &mut func.body {
+            for stmt in &mut body.stmts {
+                if let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = stmt {
+                    if let Expr::Arrow(arrow) = &mut **arg {
+                        self.scopes.push(Scope::Render);
+                        arrow.body.visit_mut_with(self);
+                        self.scopes.pop();
+                        continue;
+                    }
+                }
+                stmt.visit_mut_with(self);
+            }
+        }
+        self.scopes.pop();
+        self.hook_ordinals.pop();
+    }
+
+    /// Compile the render closures returned by setup, see the `bindings` and `lists` options
+    fn compile_render_closures(&mut self, func: &mut Function) {
+        if self.config.target != Target::Client {
+            return;
+        }
+        let Some(body) = &mut func.body else {
+            return;
+        };
+        for stmt in &mut body.stmts {
+            if let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = stmt {
+                if let Expr::Arrow(render) = &mut **arg {
+                    // Bindings first, a compiled list is not an element and would be bound as text
+                    if self.config.bindings {
+                        bindings::compile_render(render, &mut self.binding_helpers);
+                    }
+                    if self.config.lists {
+                        lists::compile_render(render, &mut self.import_map_list);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run an async setup through `useAsyncSetup`, the runtime expects setup to return the render function
+    fn wrap_async_setup(&mut self, func: &mut Function) {
+        if !func.is_async {
+            return;
+        }
+        let use_async_setup = self
+            .import_use_async_setup
+            .get_or_insert_with(|| private_ident!("useAsyncSetup"))
+            .clone();
+        async_setup::wrap_setup(func, &use_async_setup);
+    }
+
+    /// The call-site ID passed to a state hook called in setup, see the `hookIds` option
+    fn hook_id(&mut self, call: &CallExpr) -> Option<String> {
+        if !self.config.hook_ids || self.scopes.last() != Some(&Scope::Setup) {
+            return None;
+        }
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        let Expr::Ident(ident) = &**callee else {
+            return None;
+        };
+        // Only hooks imported from the import source take an ID, a local `useState` is left alone.
+        // Calls that already pass more than the hook's single argument are left alone as well
+        if !self.hook_imports.contains(&ident.to_id()) || call.args.len() != 1 || call.args[0].spread.is_some() {
+            return None;
+        }
+
+        let (component, ordinal) = self.hook_ordinals.last_mut()?;
+        let id = match &self.file {
+            Some(file) => format!("{}:{}:{}", file_hash(file), component, ordinal),
+            None => format!("{}:{}", component, ordinal),
+        };
+        *ordinal += 1;
+        Some(id)
+    }
+
+    /// The key of a component's structure and server boundary, unique across files like the hook call-site IDs
+    fn hydration_key(&self, component: &str) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}", file_hash(file), component),
+            None => component.to_string(),
+        }
+    }
+
+    /// Check if a class was produced by this transform
+    fn is_compiled_component(&self, class: &Class) -> bool {
+        let Some(super_class) = &class.super_class else {
+            return false;
+        };
+        let Expr::Ident(super_ident) = &**super_class else {
+            return false;
+        };
+        [&self.import_rask_stateful_component, &self.import_rask_stateless_component]
+            .into_iter()
+            .flatten()
+            .any(|import| import.to_id() == super_ident.to_id())
+    }
+
+    /// Run the component rules against a function that is about to be compiled and record it in the manifest
+    fn check_component(&mut self, name: &Ident, func: &Function, is_stateful: bool) {
+        let top_level = self.scopes.is_empty();
+        self.components
+            .push(manifest::describe_component(name, func, is_stateful, top_level));
+
+        if is_stateful {
+            self.explain(name.sym.as_ref(), func.span, Decision::Stateful, explain::STATEFUL_REASON);
+        } else {
+            self.explain(name.sym.as_ref(), func.span, Decision::Stateless, explain::STATELESS_REASON);
+        }
+
+        if is_stateful {
+            rules::stale_setup_read::check(func, &mut self.diagnostics);
+            rules::render_mutation::check(func, &mut self.diagnostics);
+        }
+        if is_stateful && func.is_async {
+            async_setup::check(func, &mut self.diagnostics);
+        }
+        // An async setup renders its fallback first, so the server markup can't be checked against the structure
+        if self.config.hydrate && !func.is_async {
+            let key = self.hydration_key(&name.sym);
+            match self.config.target {
+                Target::Client => {
+                    if let Some(structure) = hydrate::structure(func, is_stateful) {
+                        self.hydration.push((key, structure));
+                    }
+                }
+                Target::Ssr => self.hydration_keys.push((name.to_id(), key)),
+            }
         }
+
+        rules::list_keys::check(func, is_stateful, &mut self.diagnostics);
+        rules::hook_placement::check(func, is_stateful, &mut self.diagnostics);
+        rules::this_usage::check(func, &mut self.diagnostics);
     }
 
     /// Check if an expression contains a VNode-related call (recursive deep search)
@@ -97,7 +537,10 @@ impl RaskComponentTransform {
             // Unary expressions: !expr, +expr, etc.
             Expr::Unary(unary) => self.has_vnode_call(&unary.arg),
 
-            // JSX/Fragments - already transformed by Inferno plugin, so we won't see these
+            // JSX/Fragments - already transformed by Inferno plugin when running inside SWC,
+            // but the lint CLI parses source files directly and sees them untransformed
+            Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+
             _ => false,
         }
     }
@@ -239,7 +682,21 @@ impl RaskComponentTransform {
     }
 
     /// Transform a function declaration to a RaskStatefulComponent class
-    fn transform_to_stateful_class(&mut self, name: Ident, func: Function) -> Decl {
+    fn transform_to_stateful_class(&mut self, name: Ident, mut func: Function) -> Decl {
+        self.check_component(&name, &func, true);
+        self.visit_component_body(&name, &mut func, true);
+        self.compile_render_closures(&mut func);
+        self.wrap_async_setup(&mut func);
+
+        // The server runtime calls component functions directly
+        if self.config.target == Target::Ssr {
+            return Decl::Fn(FnDecl {
+                ident: name,
+                declare: false,
+                function: Box::new(func),
+            });
+        }
+
         // Ensure we have the RaskStatefulComponent import
         if self.import_rask_stateful_component.is_none() {
             self.import_rask_stateful_component = Some(private_ident!("RaskStatefulComponent"));
@@ -285,7 +742,18 @@ impl RaskComponentTransform {
     }
 
     /// Transform a function declaration to a RaskStatelessComponent class
-    fn transform_to_stateless_class(&mut self, name: Ident, func: Function) -> Decl {
+    fn transform_to_stateless_class(&mut self, name: Ident, mut func: Function) -> Decl {
+        self.check_component(&name, &func, false);
+        self.visit_component_body(&name, &mut func, false);
+
+        if self.config.target == Target::Ssr {
+            return Decl::Fn(FnDecl {
+                ident: name,
+                declare: false,
+                function: Box::new(func),
+            });
+        }
+
         // Ensure we have the RaskStatelessComponent import
         if self.import_rask_stateless_component.is_none() {
             self.import_rask_stateless_component = Some(private_ident!("RaskStatelessComponent"));
@@ -330,8 +798,22 @@ impl RaskComponentTransform {
         })
     }
 
-    /// Create a class expression for variable assignments
-    fn create_component_class_expr(&mut self, name: Ident, func: Function, is_stateful: bool) -> ClassExpr {
+    /// Create a class expression for variable assignments, or a function expression for the `ssr` target
+    fn create_component_expr(&mut self, name: Ident, mut func: Function, is_stateful: bool) -> Expr {
+        self.check_component(&name, &func, is_stateful);
+        self.visit_component_body(&name, &mut func, is_stateful);
+        if is_stateful {
+            self.compile_render_closures(&mut func);
+            self.wrap_async_setup(&mut func);
+        }
+
+        if self.config.target == Target::Ssr {
+            return Expr::Fn(FnExpr {
+                ident: Some(name),
+                function: Box::new(func),
+            });
+        }
+
         let super_class_ident = if is_stateful {
             if self.import_rask_stateful_component.is_none() {
                 self.import_rask_stateful_component = Some(private_ident!("RaskStatefulComponent"));
@@ -346,7 +828,7 @@ impl RaskComponentTransform {
 
         let prop_key = if is_stateful { "setup" } else { "renderFn" };
 
-        ClassExpr {
+        Expr::Class(ClassExpr {
             ident: Some(name.clone()),
             class: Box::new(Class {
                 span: Default::default(),
@@ -376,7 +858,7 @@ impl RaskComponentTransform {
                 super_type_params: None,
                 implements: vec![],
             }),
-        }
+        })
     }
 
     /// Convert arrow function to regular function for analysis
@@ -519,6 +1001,31 @@ impl RaskComponentTransform {
             }
         }
 
+        // The private identifier can't clash with an existing import
+        if let Some(use_async_setup) = &self.import_use_async_setup {
+            specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                span: Default::default(),
+                local: use_async_setup.clone(),
+                imported: Some(ModuleExportName::Ident(quote_ident!("useAsyncSetup").into())),
+                is_type_only: false,
+            }));
+        }
+
+        for (helper, name) in [
+            (&self.binding_helpers.bind_text, "bindText"),
+            (&self.binding_helpers.bind_attrs, "bindAttrs"),
+            (&self.import_map_list, "mapList"),
+        ] {
+            if let Some(local) = helper {
+                specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: Default::default(),
+                    local: local.clone(),
+                    imported: Some(ModuleExportName::Ident(quote_ident!(name).into())),
+                    is_type_only: false,
+                }));
+            }
+        }
+
         // Only create import if we have specifiers to add
         if !specifiers.is_empty() {
             let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
@@ -543,9 +1050,53 @@ impl VisitMut for RaskComponentTransform {
     noop_visit_mut_type!();
 
     fn visit_mut_module(&mut self, module: &mut Module) {
+        let import_source = self.config.import_source.as_deref().unwrap_or("rask-ui");
+        rules::missing_import::check(module, import_source, &mut self.diagnostics);
+        self.routes = routes::check(module, &mut self.diagnostics);
+        if self.config.hook_ids {
+            self.hook_imports = hook_imports(module, import_source);
+        }
+
+        // Stubs replace server code before it is visited, so nothing from it is compiled for the client
+        if self.config.server_actions {
+            let file_hash = self.file.as_deref().map(file_hash);
+            server_actions::compile_module(
+                module,
+                file_hash.as_deref(),
+                self.config.target,
+                import_source,
+                &mut self.diagnostics,
+            );
+        }
+
+        // Imports only used as islands are loaded lazily by the client, so the page does not bundle the island
+        if self.config.target == Target::Client {
+            self.lazy_imports.extend(islands::island_imports(module));
+        }
+        self.lazy = lazy::compile_module(module, &self.lazy_imports, import_source);
+
         // First visit all items to transform them
         module.visit_mut_children_with(self);
 
+        // Exports are resolved after the transform, when components have become classes
+        manifest::resolve_exports(module, &mut self.components);
+
+        // Islands keep their `client:*` directive until the `ssr` target renders their boundaries
+        self.islands = islands::collect(module, self.config.target == Target::Client, &self.lazy);
+
+        let import_source = self.config.import_source.as_deref().unwrap_or("rask-ui");
+        match self.config.target {
+            Target::Ssr => {
+                ssr::compile_module(module, import_source, self.config.hydrate);
+                hydrate::key_components(module, &self.hydration_keys);
+            }
+            Target::Client if self.config.hydrate => {
+                hydrate::register_structures(module, &self.hydration, import_source)
+            }
+            Target::Client => {}
+        }
+        islands::append_loaders(module, &self.islands);
+
         // Rewrite any "inferno" imports to use the configured import source
         self.rewrite_inferno_imports(module);
 
@@ -555,36 +1106,68 @@ impl VisitMut for RaskComponentTransform {
 
     fn visit_mut_function(&mut self, func: &mut Function) {
         // Visit the function body to find nested components
-        if let Some(body) = &mut func.body {
-            for stmt in &mut body.stmts {
-                self.visit_mut_stmt(stmt);
-            }
-        }
+        self.scopes.push(Scope::Function);
         func.visit_mut_children_with(self);
+        self.scopes.pop();
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        self.scopes.push(Scope::Function);
+        arrow.visit_mut_children_with(self);
+        self.scopes.pop();
+    }
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        // The ID is taken before visiting the arguments, so ordinals follow the source order
+        if let Some(id) = self.hook_id(call) {
+            call.args.push(ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Lit(Lit::Str(Str {
+                    span: Default::default(),
+                    value: Wtf8Atom::from(id.as_str()),
+                    raw: None,
+                }))),
+            });
+        }
+        call.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        // Compiled components already had their bodies visited with setup and render scopes
+        if self.is_compiled_component(class) {
+            return;
+        }
+        class.visit_mut_children_with(self);
     }
 
     fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
         match stmt {
             // Handle: function MyComponent() { return () => <div /> }
             Stmt::Decl(Decl::Fn(fn_decl)) => {
-                if self.is_rask_component(&fn_decl.function) {
-                    let name = fn_decl.ident.clone();
-                    let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateful_class(name, func);
-                    *stmt = Stmt::Decl(class_decl);
-                    return;
-                } else if self.is_stateless_component(&fn_decl.function) {
+                let is_stateful = self.is_rask_component(&fn_decl.function);
+                let is_stateless = !is_stateful && self.is_stateless_component(&fn_decl.function);
+
+                if (is_stateful || is_stateless) && self.allow_nested_component(&fn_decl.ident) {
                     let name = fn_decl.ident.clone();
                     let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateless_class(name, func);
+                    let class_decl = if is_stateful {
+                        self.transform_to_stateful_class(name, func)
+                    } else {
+                        self.transform_to_stateless_class(name, func)
+                    };
                     *stmt = Stmt::Decl(class_decl);
                     return;
+                } else if !is_stateful && !is_stateless {
+                    let name = fn_decl.ident.sym.clone();
+                    self.explain_skipped(&name, &fn_decl.function);
                 }
             }
 
             // Handle: const MyComponent = () => { return () => <div /> }
             Stmt::Decl(Decl::Var(var_decl)) => {
                 for decl in &mut var_decl.decls {
+                    // Compiled components were visited while compiling, visiting them again would compile them twice
+                    let mut compiled = false;
                     if let Some(init) = &mut decl.init {
                         // Check if it's an arrow function
                         if let Expr::Arrow(arrow) = &**init {
@@ -595,14 +1178,31 @@ impl VisitMut for RaskComponentTransform {
                             if is_stateful || is_stateless {
                                 // Get the variable name
                                 if let Pat::Ident(ident_pat) = &decl.name {
-                                    let name = ident_pat.id.clone();
-                                    let class_expr = self.create_component_class_expr(name, func, is_stateful);
-                                    *init = Box::new(Expr::Class(class_expr));
+                                    if self.allow_nested_component(&ident_pat.id) {
+                                        let name = ident_pat.id.clone();
+                                        *init = Box::new(self.create_component_expr(name, func, is_stateful));
+                                        compiled = true;
+                                    }
                                 }
+                            } else if let Pat::Ident(ident_pat) = &decl.name {
+                                self.explain_skipped(&ident_pat.id.sym, &func);
+                            }
+                        } else if let (Expr::Fn(fn_expr), Pat::Ident(ident_pat)) = (&**init, &decl.name) {
+                            if explain::is_candidate_name(&ident_pat.id.sym) {
+                                self.explain(
+                                    &ident_pat.id.sym,
+                                    fn_expr.function.span,
+                                    Decision::Skipped,
+                                    explain::FUNCTION_EXPRESSION_REASON,
+                                );
                             }
                         }
                     }
+                    if !compiled {
+                        decl.visit_mut_with(self);
+                    }
                 }
+                return;
             }
 
             _ => {}
@@ -629,6 +1229,12 @@ impl VisitMut for RaskComponentTransform {
                     let class_decl = self.transform_to_stateless_class(name, func);
                     *item = ModuleItem::Stmt(Stmt::Decl(class_decl));
                     return;
+                } else {
+                    let name = fn_decl.ident.sym.clone();
+                    self.explain_skipped(&name, &fn_decl.function);
+                    // Explained here, visiting the statement must not explain it again
+                    fn_decl.function.visit_mut_with(self);
+                    return;
                 }
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
@@ -646,13 +1252,21 @@ impl VisitMut for RaskComponentTransform {
                                 .unwrap_or_else(|| quote_ident!("DefaultComponent").into());
 
                             let func = (*fn_expr.function).clone();
-                            let class_expr = self.create_component_class_expr(name, func, is_stateful);
-                            export.decl = DefaultDecl::Class(ClassExpr {
-                                ident: class_expr.ident,
-                                class: class_expr.class,
-                            });
+                            export.decl = match self.create_component_expr(name, func, is_stateful) {
+                                Expr::Fn(fn_expr) => DefaultDecl::Fn(fn_expr),
+                                Expr::Class(class_expr) => DefaultDecl::Class(class_expr),
+                                _ => unreachable!("components compile to classes or functions"),
+                            };
                             return;
                         }
+
+                        let name = fn_expr
+                            .ident
+                            .as_ref()
+                            .map(|ident| ident.sym.to_string())
+                            .unwrap_or_else(|| "default".to_string());
+                        let reason = self.skip_reason(&fn_expr.function);
+                        self.explain(&name, fn_expr.function.span, Decision::Skipped, reason);
                     }
                     // No need to handle DefaultDecl::Class as it's already a class
                     _ => {}
@@ -668,10 +1282,12 @@ impl VisitMut for RaskComponentTransform {
 
                     if is_stateful || is_stateless {
                         let name = quote_ident!("DefaultComponent").into();
-                        let class_expr = self.create_component_class_expr(name, func, is_stateful);
-                        export.expr = Box::new(Expr::Class(class_expr));
+                        export.expr = Box::new(self.create_component_expr(name, func, is_stateful));
                         return;
                     }
+
+                    let reason = self.skip_reason(&func);
+                    self.explain("default", func.span, Decision::Skipped, reason);
                 }
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
@@ -692,6 +1308,26 @@ impl VisitMut for RaskComponentTransform {
                         let class_decl = self.transform_to_stateless_class(name, func);
                         export.decl = class_decl;
                         return;
+                    } else {
+                        let name = fn_decl.ident.sym.clone();
+                        self.explain_skipped(&name, &fn_decl.function);
+                    }
+                }
+
+                // Handle: export const MyComponent = () => ... which is not compiled
+                if let Decl::Var(var_decl) = &export.decl {
+                    for decl in &var_decl.decls {
+                        let (Pat::Ident(ident_pat), Some(init)) = (&decl.name, &decl.init) else {
+                            continue;
+                        };
+                        if matches!(&**init, Expr::Arrow(_) | Expr::Fn(_)) && explain::is_candidate_name(&ident_pat.id.sym) {
+                            self.explain(
+                                &ident_pat.id.sym,
+                                init.span(),
+                                Decision::Skipped,
+                                explain::UNSUPPORTED_EXPORT_REASON,
+                            );
+                        }
                     }
                 }
             }
@@ -702,6 +1338,99 @@ impl VisitMut for RaskComponentTransform {
     }
 }
 
+impl RaskComponentTransform {
+    /// Emit the diagnostics, and the explanations when enabled, through the current error handler
+    pub fn emit_diagnostics(&self) {
+        for diagnostic in &self.diagnostics {
+            diagnostic.emit();
+        }
+
+        if self.config.explain {
+            HANDLER.with(|handler| {
+                for explanation in &self.explanations {
+                    handler.span_note_without_error(
+                        explanation.span,
+                        &format!(
+                            "[rask/explain] `{}` {}: {}",
+                            explanation.name,
+                            explanation.decision.as_str(),
+                            explanation.reason
+                        ),
+                    );
+                }
+            });
+        }
+    }
+
+    /// Attach the explanation and manifest comments enabled in the config to the transformed output.
+    /// The manifest names the file given to [`Self::with_file`], the same path the hook IDs are built from.
+    pub fn add_comments<C: Comments, S: SourceMapper + ?Sized>(&self, comments: &C, source_map: &S, program_lo: BytePos) {
+        if self.config.explain_comments {
+            for explanation in &self.explanations {
+                comments.add_leading(
+                    explanation.span.lo,
+                    Comment {
+                        kind: CommentKind::Block,
+                        span: Default::default(),
+                        text: format!(" rask: {} ", explanation.decision.as_str()).into(),
+                    },
+                );
+            }
+        }
+
+        if self.config.manifest {
+            let mut components = self.components.clone();
+            for component in &mut components {
+                component.location = Location::from_span(source_map, component.span);
+            }
+            let mut islands = self.islands.clone();
+            for island in &mut islands {
+                island.location = Location::from_span(source_map, island.span);
+            }
+            let mut lazy = self.lazy.clone();
+            for component in &mut lazy {
+                component.location = Location::from_span(source_map, component.span);
+            }
+            let mut routes = self.routes.clone();
+            for route in &mut routes {
+                route.location = Location::from_span(source_map, route.span);
+            }
+            let manifest = ModuleManifest {
+                version: MANIFEST_VERSION,
+                file: self.file.clone(),
+                components,
+                islands,
+                lazy,
+                routes,
+            };
+
+            if let Ok(json) = serde_json::to_string(&manifest) {
+                comments.add_leading(
+                    program_lo,
+                    Comment {
+                        kind: CommentKind::Block,
+                        span: Default::default(),
+                        // `*/` can only appear inside JSON strings, where `\/` is an equivalent escape
+                        text: format!(" @rask-manifest {} ", json.replace("*/", "*\\/")).into(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// The Rask component transform as a pass, for Rust tooling that runs SWC passes natively.
+/// Diagnostics are emitted through the error handler set for the current thread.
+pub fn rask_transform(config: Config) -> impl Pass {
+    move |program: &mut Program| {
+        let mut transform = RaskComponentTransform::new(config.clone());
+        program.visit_mut_with(&mut transform);
+        transform.emit_diagnostics();
+    }
+}
+
+// The browser build has no plugin host to provide the plugin imports
+#[cfg(not(feature = "playground"))]
 #[plugin_transform]
 pub fn process_transform(mut program: Program, metadata: TransformPluginProgramMetadata) -> Program {
     let config = serde_json::from_str::<Config>(
@@ -711,6 +1440,31 @@ pub fn process_transform(mut program: Program, metadata: TransformPluginProgramM
     )
     .unwrap_or_default();
 
-    program.visit_mut_with(&mut RaskComponentTransform::new(config));
+    let filename = metadata.get_context(&TransformPluginMetadataContextKind::Filename);
+    let cwd = metadata.get_context(&TransformPluginMetadataContextKind::Cwd);
+    let relative = filename.as_ref().map(|filename| match &cwd {
+        Some(cwd) => filename
+            .strip_prefix(cwd.as_str())
+            .map(|relative| relative.trim_start_matches(['/', '\\']))
+            .unwrap_or(filename)
+            .to_string(),
+        None => filename.clone(),
+    });
+
+    let lazy_imports = match (&program, &metadata.comments) {
+        (Program::Module(module), Some(comments)) => lazy::annotated_imports(module, comments),
+        _ => vec![],
+    };
+
+    let mut transform = RaskComponentTransform::new(config)
+        .with_file(relative)
+        .with_lazy_imports(lazy_imports);
+    program.visit_mut_with(&mut transform);
+    transform.emit_diagnostics();
+
+    if let Some(comments) = &metadata.comments {
+        transform.add_comments(comments, &metadata.source_map, program.span_lo());
+    }
+
     program
 }
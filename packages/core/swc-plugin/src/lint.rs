@@ -0,0 +1,139 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use swc_core::common::{comments::SingleThreadedComments, sync::Lrc, FileName, SourceFile, SourceMap, GLOBALS};
+use swc_core::ecma::visit::VisitMutWith;
+
+use crate::compile::{parse_source, syntax_for};
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::explain::Explanation;
+use crate::lazy;
+use crate::location::Location;
+use crate::manifest::{ModuleManifest, MANIFEST_VERSION};
+use crate::{Config, RaskComponentTransform};
+
+/// A source file together with everything the analysis reported for it
+pub struct LintedFile {
+    pub path: PathBuf,
+    pub source: Lrc<SourceFile>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub manifest: ModuleManifest,
+    pub explanations: Vec<Explanation>,
+}
+
+impl LintedFile {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+/// Expand the given files and directories into the `.tsx`/`.jsx` files to lint
+pub fn collect_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+        if path.is_dir() {
+            // Skip dependencies, build output and hidden directories
+            if name == "node_modules" || name == "dist" || name == "target" || name.starts_with('.') {
+                continue;
+            }
+            collect_dir(&path, files)?;
+        } else if is_component_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_component_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("tsx") | Some("jsx")
+    )
+}
+
+/// Parse a file and run the component classification and rules over it
+pub fn lint_file(cm: &Lrc<SourceMap>, path: &Path, config: &Config) -> io::Result<LintedFile> {
+    let source = cm.load_file(path)?;
+    Ok(lint_loaded(cm, path, source, config))
+}
+
+/// Lint the unsaved contents of a file, as an editor has them
+pub fn lint_source(cm: &Lrc<SourceMap>, path: &Path, text: String, config: &Config) -> LintedFile {
+    let source = cm.new_source_file(Lrc::new(FileName::Real(path.to_path_buf())), text);
+    lint_loaded(cm, path, source, config)
+}
+
+fn lint_loaded(cm: &Lrc<SourceMap>, path: &Path, source: Lrc<SourceFile>, config: &Config) -> LintedFile {
+    let mut diagnostics = vec![];
+    let mut components = vec![];
+    let mut explanations = vec![];
+    let mut islands = vec![];
+    let mut lazy = vec![];
+    let mut routes = vec![];
+    // Comments are only read for `/* @rask-lazy */` imports
+    let comments = SingleThreadedComments::default();
+
+    if let Some(mut module) = parse_source(&source, syntax_for(path), Some(&comments), &mut diagnostics) {
+        GLOBALS.set(&Default::default(), || {
+            let mut transform =
+                RaskComponentTransform::new(config.clone()).with_lazy_imports(lazy::annotated_imports(&module, &comments));
+            module.visit_mut_with(&mut transform);
+            diagnostics.extend(transform.diagnostics);
+            components = transform.components;
+            explanations = transform.explanations;
+            islands = transform.islands;
+            lazy = transform.lazy;
+            routes = transform.routes;
+        });
+    }
+
+    for component in &mut components {
+        component.location = Location::from_span(&**cm, component.span);
+    }
+    for explanation in &mut explanations {
+        explanation.location = Location::from_span(&**cm, explanation.span);
+    }
+    for island in &mut islands {
+        island.location = Location::from_span(&**cm, island.span);
+    }
+    for component in &mut lazy {
+        component.location = Location::from_span(&**cm, component.span);
+    }
+    for route in &mut routes {
+        route.location = Location::from_span(&**cm, route.span);
+    }
+
+    LintedFile {
+        path: path.to_path_buf(),
+        source,
+        diagnostics,
+        manifest: ModuleManifest {
+            version: MANIFEST_VERSION,
+            file: Some(path.display().to_string()),
+            components,
+            islands,
+            lazy,
+            routes,
+        },
+        explanations,
+    }
+}
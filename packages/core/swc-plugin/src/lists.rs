@@ -0,0 +1,153 @@
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    utils::{find_pat_ids, private_ident},
+    visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use crate::analysis::{callee_method, unwrap_expr};
+use crate::ssr::{arg, factory_name};
+
+/// Compile the `.map` calls of a render closure rendering an element per item into `mapList`.
+/// `todos.map((todo) => <Todo key={todo.id} todo={todo} />)` becomes
+/// `mapList(todos, (todo) => <Todo ... />, (todo) => todo.id)`, which renders each item in its own
+/// reactive boundary, so changing `todo.done` renders that item again instead of the whole list.
+pub fn compile_render(render: &mut ArrowExpr, map_list: &mut Option<Ident>) {
+    render.body.visit_mut_with(&mut ListCompiler { map_list });
+}
+
+struct ListCompiler<'a> {
+    map_list: &'a mut Option<Ident>,
+}
+
+impl VisitMut for ListCompiler<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        let Expr::Call(call) = expr else {
+            return;
+        };
+        let Some((items, key)) = list_call(call) else {
+            return;
+        };
+        let span = call.span;
+        let render = call.args[0].expr.clone();
+
+        let map_list = self.map_list.get_or_insert_with(|| private_ident!("mapList")).clone();
+        let mut args = vec![items, *render];
+        if let Some(key) = key {
+            args.push(key);
+        }
+        *expr = Expr::Call(CallExpr {
+            span,
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(Expr::Ident(map_list))),
+            args: args
+                .into_iter()
+                .map(|expr| ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(expr),
+                })
+                .collect(),
+            type_args: None,
+        });
+    }
+}
+
+/// The list and the key function of `items.map(render)`, when `render` returns an element for each item
+fn list_call(call: &CallExpr) -> Option<(Expr, Option<Expr>)> {
+    let (items, "map") = callee_method(call)? else {
+        return None;
+    };
+    if call.args.len() != 1 || call.args[0].spread.is_some() {
+        return None;
+    }
+    let Expr::Arrow(render) = &*call.args[0].expr else {
+        return None;
+    };
+    // Items are rendered with their index, but not the array as the third argument
+    if render.is_async || render.is_generator || render.params.len() > 2 {
+        return None;
+    }
+
+    let (element, declared) = match &*render.body {
+        BlockStmtOrExpr::Expr(expr) => (unwrap_expr(expr), vec![]),
+        BlockStmtOrExpr::BlockStmt(block) => match block.stmts.last() {
+            Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) => (unwrap_expr(arg), declared_ids(block)),
+            _ => return None,
+        },
+    };
+    let Expr::Call(element) = element else {
+        return None;
+    };
+    let key_index = match factory_name(element)? {
+        // `createVNode(flags, tag, className, children, childFlags, props, key, ref)`
+        "createVNode" => 6,
+        // `createComponentVNode(flags, Type, props, key, ref)`
+        "createComponentVNode" => 3,
+        // `createFragment(children, childFlags, key)`
+        "createFragment" => 2,
+        _ => return None,
+    };
+
+    let Some(key) = arg(element, key_index) else {
+        return Some((items.clone(), None));
+    };
+    // The key is read by the list, without the statements of the render function
+    if uses_any(key, &declared) {
+        return None;
+    }
+    let key = Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: Default::default(),
+        params: render.params.clone(),
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(key.clone()))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    });
+    Some((items.clone(), Some(key)))
+}
+
+/// Variables declared at the top level of a render function's body
+fn declared_ids(block: &BlockStmt) -> Vec<Id> {
+    block
+        .stmts
+        .iter()
+        .flat_map(|stmt| match stmt {
+            Stmt::Decl(Decl::Var(var)) => var
+                .decls
+                .iter()
+                .flat_map(|declarator| find_pat_ids::<_, Id>(&declarator.name))
+                .collect(),
+            Stmt::Decl(Decl::Fn(fn_decl)) => vec![fn_decl.ident.to_id()],
+            Stmt::Decl(Decl::Class(class_decl)) => vec![class_decl.ident.to_id()],
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn uses_any(expr: &Expr, ids: &[Id]) -> bool {
+    if ids.is_empty() {
+        return false;
+    }
+    let mut finder = IdentFinder { ids, found: false };
+    expr.visit_with(&mut finder);
+    finder.found
+}
+
+struct IdentFinder<'a> {
+    ids: &'a [Id],
+    found: bool,
+}
+
+impl Visit for IdentFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.found |= self.ids.contains(&ident.to_id());
+    }
+}
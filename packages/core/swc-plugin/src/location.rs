@@ -0,0 +1,40 @@
+use serde::Serialize;
+use swc_core::common::{errors::SourceMapper, BytePos, Span};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in Unicode code points
+    pub column: usize,
+    /// Byte offset into the file
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Location {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Location {
+    /// Resolve a span to line and column positions, `None` for spans without a source position
+    pub fn from_span<C: SourceMapper + ?Sized>(cm: &C, span: Span) -> Option<Self> {
+        if span.is_dummy() {
+            return None;
+        }
+        let position = |pos: BytePos| {
+            let loc = cm.lookup_char_pos(pos);
+            let file_start = loc.file.start_pos;
+            Position {
+                line: loc.line,
+                column: loc.col.0 + 1,
+                offset: (pos - file_start).0 as usize,
+            }
+        };
+        Some(Location {
+            start: position(span.lo),
+            end: position(span.hi),
+        })
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_method, callee_name, setup_stmts, unwrap_expr, SETUP_HOOKS};
+use crate::islands::IslandManifest;
+use crate::lazy::LazyManifest;
+use crate::location::Location;
+use crate::routes::RouteManifest;
+
+/// Version of the manifest format, bumped on breaking changes
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentKind {
+    Stateful,
+    Stateless,
+}
+
+/// Static description of a compiled component
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentManifest {
+    pub name: String,
+    /// Name the component is exported as, `"default"` for default exports
+    pub export_name: Option<String>,
+    pub kind: ComponentKind,
+    #[serde(skip)]
+    pub span: Span,
+    pub location: Option<Location>,
+    /// Hooks called in setup, in call order
+    pub hooks: Vec<String>,
+    /// Keys read from props
+    pub props: Vec<String>,
+    pub contexts_used: Vec<String>,
+    pub contexts_injected: Vec<String>,
+    /// Declared at module level rather than inside another function
+    #[serde(skip)]
+    pub top_level: bool,
+}
+
+/// Every component compiled from a single module
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleManifest {
+    pub version: u32,
+    pub file: Option<String>,
+    pub components: Vec<ComponentManifest>,
+    /// Component usages marked with `client:*` directives, loaded by `bootIslands`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub islands: Vec<IslandManifest>,
+    /// Components loaded from their own chunk, which can be preloaded
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lazy: Vec<LazyManifest>,
+    /// Routes of the `useRouter` configs read at build time
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<RouteManifest>,
+}
+
+/// Manifests of every module in a project, as aggregated by the CLI
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectManifest {
+    pub version: u32,
+    pub modules: Vec<ModuleManifest>,
+}
+
+/// A route and the module declaring it
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectRoute {
+    pub file: Option<String>,
+    #[serde(flatten)]
+    pub route: RouteManifest,
+}
+
+/// Routes of every module in a project, as exported by the CLI for sitemaps and link checking
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteManifestFile {
+    pub version: u32,
+    pub routes: Vec<ProjectRoute>,
+}
+
+/// Describe a component from the function it is compiled from
+pub fn describe_component(name: &Ident, func: &Function, is_stateful: bool, top_level: bool) -> ComponentManifest {
+    let mut collector = SetupCollector::default();
+    if is_stateful {
+        for stmt in setup_stmts(func) {
+            stmt.visit_with(&mut collector);
+        }
+    } else {
+        func.body.visit_with(&mut collector);
+    }
+
+    let mut props = BTreeSet::new();
+    match func.params.first().map(|param| &param.pat) {
+        Some(Pat::Ident(binding)) => {
+            let mut prop_reads = PropReads {
+                props: binding.id.to_id(),
+                keys: &mut props,
+            };
+            func.body.visit_with(&mut prop_reads);
+        }
+        Some(Pat::Object(object_pat)) => {
+            for prop in &object_pat.props {
+                match prop {
+                    ObjectPatProp::KeyValue(key_value) => {
+                        if let Some(key) = prop_name_key(&key_value.key) {
+                            props.insert(key);
+                        }
+                    }
+                    ObjectPatProp::Assign(assign) => {
+                        props.insert(assign.key.id.sym.to_string());
+                    }
+                    ObjectPatProp::Rest(_) => {}
+                }
+            }
+        }
+        _ => {}
+    }
+
+    ComponentManifest {
+        name: name.sym.to_string(),
+        export_name: None,
+        kind: if is_stateful {
+            ComponentKind::Stateful
+        } else {
+            ComponentKind::Stateless
+        },
+        span: func.span,
+        location: None,
+        hooks: collector.hooks,
+        props: props.into_iter().collect(),
+        contexts_used: collector.contexts_used.into_iter().collect(),
+        contexts_injected: collector.contexts_injected.into_iter().collect(),
+        top_level,
+    }
+}
+
+/// Fill in the export names of top-level components from the module's exports
+pub fn resolve_exports(module: &Module, components: &mut [ComponentManifest]) {
+    let mut exports: Vec<(String, String)> = vec![];
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+        match decl {
+            ModuleDecl::ExportDecl(export) => match &export.decl {
+                Decl::Class(class) => exports.push((class.ident.sym.to_string(), class.ident.sym.to_string())),
+                Decl::Fn(func) => exports.push((func.ident.sym.to_string(), func.ident.sym.to_string())),
+                Decl::Var(var_decl) => {
+                    for decl in &var_decl.decls {
+                        if let Pat::Ident(binding) = &decl.name {
+                            exports.push((binding.id.sym.to_string(), binding.id.sym.to_string()));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            ModuleDecl::ExportDefaultDecl(export) => {
+                let ident = match &export.decl {
+                    DefaultDecl::Class(class) => class.ident.as_ref(),
+                    DefaultDecl::Fn(func) => func.ident.as_ref(),
+                    _ => None,
+                };
+                if let Some(ident) = ident {
+                    exports.push((ident.sym.to_string(), "default".to_string()));
+                }
+            }
+            ModuleDecl::ExportDefaultExpr(export) => match unwrap_expr(&export.expr) {
+                Expr::Ident(ident) => exports.push((ident.sym.to_string(), "default".to_string())),
+                Expr::Class(ClassExpr { ident: Some(ident), .. }) | Expr::Fn(FnExpr { ident: Some(ident), .. }) => {
+                    exports.push((ident.sym.to_string(), "default".to_string()))
+                }
+                _ => {}
+            },
+            ModuleDecl::ExportNamed(named) if named.src.is_none() => {
+                for specifier in &named.specifiers {
+                    if let ExportSpecifier::Named(specifier) = specifier {
+                        let local = module_export_name(&specifier.orig);
+                        let exported = specifier
+                            .exported
+                            .as_ref()
+                            .map(module_export_name)
+                            .unwrap_or_else(|| local.clone());
+                        exports.push((local, exported));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for component in components.iter_mut().filter(|component| component.top_level) {
+        if let Some((_, exported)) = exports.iter().find(|(local, _)| *local == component.name) {
+            component.export_name = Some(exported.clone());
+        }
+    }
+}
+
+fn module_export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string_lossy().into_owned(),
+    }
+}
+
+fn prop_name_key(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(str) => Some(str.value.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct SetupCollector {
+    hooks: Vec<String>,
+    contexts_used: BTreeSet<String>,
+    contexts_injected: BTreeSet<String>,
+}
+
+impl Visit for SetupCollector {
+    noop_visit_type!();
+
+    // Only calls made while setup runs belong to the component
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(name) = callee_name(call) {
+            if SETUP_HOOKS.contains(&name) {
+                self.hooks.push(name.to_string());
+            }
+        } else if let Some((obj, method)) = callee_method(call) {
+            if let Expr::Ident(context) = unwrap_expr(obj) {
+                match method {
+                    "use" => {
+                        self.contexts_used.insert(context.sym.to_string());
+                    }
+                    "inject" => {
+                        self.contexts_injected.insert(context.sym.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// Collects the keys read from the props parameter, e.g. `title` in `props.title`
+struct PropReads<'a> {
+    props: Id,
+    keys: &'a mut BTreeSet<String>,
+}
+
+impl Visit for PropReads<'_> {
+    noop_visit_type!();
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let Expr::Ident(obj) = unwrap_expr(&member.obj) {
+            if obj.to_id() == self.props {
+                match &member.prop {
+                    MemberProp::Ident(prop) => {
+                        self.keys.insert(prop.sym.to_string());
+                    }
+                    MemberProp::Computed(ComputedPropName { expr, .. }) => {
+                        if let Expr::Lit(Lit::Str(key)) = unwrap_expr(expr) {
+                            self.keys.insert(key.value.to_string_lossy().into_owned());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        member.visit_children_with(self);
+    }
+}
@@ -0,0 +1,77 @@
+use serde::Serialize;
+use swc_core::common::{sync::Lrc, FileName, SourceMap};
+use wasm_bindgen::prelude::*;
+
+use crate::compile::{transform_file, Output};
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::explain::Explanation;
+use crate::location::Location;
+use crate::manifest::ComponentManifest;
+use crate::Config;
+
+#[derive(Serialize)]
+struct PlaygroundResult {
+    /// `None` when the module has errors and could not be compiled
+    code: Option<String>,
+    diagnostics: Vec<PlaygroundDiagnostic>,
+    components: Vec<ComponentManifest>,
+    explanations: Vec<Explanation>,
+}
+
+#[derive(Serialize)]
+struct PlaygroundDiagnostic {
+    rule: &'static str,
+    severity: &'static str,
+    message: String,
+    suggestion: Option<String>,
+    location: Option<Location>,
+}
+
+/// Compile a module for the docs playground.
+/// Takes the plugin config as JSON and returns a JSON object with the code, diagnostics and classification.
+#[wasm_bindgen]
+pub fn transform(source: &str, filename: &str, config: &str) -> Result<String, JsError> {
+    let config: Config = if config.trim().is_empty() {
+        Config::default()
+    } else {
+        serde_json::from_str(config).map_err(|err| JsError::new(&format!("Invalid config: {}", err)))?
+    };
+
+    let cm: Lrc<SourceMap> = Default::default();
+    let file = cm.new_source_file(Lrc::new(FileName::Custom(filename.to_string())), source.to_string());
+
+    let (code, diagnostics, components, explanations) = match transform_file(&cm, &file, filename, &config) {
+        Ok(Output {
+            code,
+            diagnostics,
+            components,
+            explanations,
+        }) => (Some(code), diagnostics, components, explanations),
+        Err(diagnostics) => (None, diagnostics.0, vec![], vec![]),
+    };
+
+    let result = PlaygroundResult {
+        code,
+        diagnostics: diagnostics
+            .into_iter()
+            .map(|diagnostic| playground_diagnostic(&cm, diagnostic))
+            .collect(),
+        components,
+        explanations,
+    };
+
+    serde_json::to_string(&result).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn playground_diagnostic(cm: &SourceMap, diagnostic: Diagnostic) -> PlaygroundDiagnostic {
+    PlaygroundDiagnostic {
+        rule: diagnostic.rule,
+        severity: match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        location: Location::from_span(cm, diagnostic.span),
+        message: diagnostic.message,
+        suggestion: diagnostic.suggestion,
+    }
+}
@@ -0,0 +1,592 @@
+use std::collections::HashSet;
+
+use swc_core::common::{BytePos, Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    atoms::Atom,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{hook_call_name, is_hook_stmt, member_root, setup_stmts, ReactiveBindings};
+use crate::diagnostics::{Fix, TextEdit};
+use crate::manifest::ComponentKind;
+use crate::RaskComponentTransform;
+
+/// A function the transform compiles into a component, found in an untransformed module
+pub struct ComponentDecl {
+    pub name: String,
+    pub kind: ComponentKind,
+    /// Span of the whole declaration, used to find the component at a position
+    pub span: Span,
+    pub func: Function,
+}
+
+/// The source text the spans of a module point into
+#[derive(Clone, Copy)]
+pub struct SourceText<'a> {
+    pub text: &'a str,
+    pub start_pos: BytePos,
+}
+
+impl<'a> SourceText<'a> {
+    pub(crate) fn slice(&self, span: Span) -> Option<&'a str> {
+        let lo = span.lo.0.checked_sub(self.start_pos.0)? as usize;
+        let hi = span.hi.0.checked_sub(self.start_pos.0)? as usize;
+        self.text.get(lo..hi)
+    }
+
+    /// Leading whitespace of the line containing the position
+    pub(crate) fn indent_at(&self, pos: BytePos) -> &'a str {
+        let offset = (pos.0.saturating_sub(self.start_pos.0) as usize).min(self.text.len());
+        let line_start = self.text[..offset].rfind('\n').map(|index| index + 1).unwrap_or(0);
+        let line = &self.text[line_start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// Start of every line that begins inside the span, with the text of the line.
+    /// Blank lines and lines inside template literals of the statements are skipped, their indentation is not code.
+    pub(crate) fn line_starts<'s>(
+        &self,
+        span: Span,
+        stmts: impl IntoIterator<Item = &'s Stmt>,
+    ) -> Vec<(BytePos, &'a str)> {
+        let Some(text) = self.slice(span) else {
+            return vec![];
+        };
+        let mut templates = TemplateSpans::default();
+        for stmt in stmts {
+            stmt.visit_with(&mut templates);
+        }
+        text.match_indices('\n')
+            .map(|(offset, _)| {
+                let line = text[offset + 1..].split('\n').next().unwrap_or_default();
+                (span.lo + BytePos(offset as u32 + 1), line)
+            })
+            .filter(|(pos, line)| {
+                let in_template = templates
+                    .spans
+                    .iter()
+                    .any(|template| template.lo < *pos && *pos < template.hi);
+                !line.trim().is_empty() && !in_template
+            })
+            .collect()
+    }
+
+    /// Grow a span to the lines it covers when nothing else is on them, so deleting it leaves no blank line
+    pub(crate) fn whole_lines(&self, span: Span) -> Span {
+        let (Some(lo), Some(hi)) = (
+            span.lo.0.checked_sub(self.start_pos.0).map(|lo| lo as usize),
+            span.hi.0.checked_sub(self.start_pos.0).map(|hi| hi as usize),
+        ) else {
+            return span;
+        };
+        let (Some(before), Some(after)) = (self.text.get(..lo), self.text.get(hi..)) else {
+            return span;
+        };
+
+        let rest_before = before.trim_end_matches([' ', '\t']);
+        let rest_after = after.trim_start_matches([' ', '\t']);
+        let leading = before.len() - rest_before.len();
+        let trailing = after.len() - rest_after.len();
+        if !(rest_before.is_empty() || rest_before.ends_with('\n')) || !rest_after.starts_with('\n') {
+            return span;
+        }
+
+        Span::new(
+            BytePos(span.lo.0 - leading as u32),
+            BytePos(span.hi.0 + trailing as u32 + 1),
+        )
+    }
+}
+
+/// Every function in the module that the transform compiles into a component
+pub fn find_components(module: &Module) -> Vec<ComponentDecl> {
+    let mut finder = ComponentFinder { components: vec![] };
+    module.visit_with(&mut finder);
+    finder.components
+}
+
+/// The innermost component declared around the position
+pub fn component_at(module: &Module, pos: BytePos) -> Option<ComponentDecl> {
+    find_components(module)
+        .into_iter()
+        .filter(|component| component.span.lo <= pos && pos <= component.span.hi)
+        .min_by_key(|component| component.span.hi.0 - component.span.lo.0)
+}
+
+/// Turn a stateless component into a stateful one by returning its output from a render function.
+/// Hooks move into setup, the other statements move into the render function.
+/// Returns why the component can not be converted otherwise.
+pub fn to_stateful(func: &Function, source: SourceText) -> Result<Fix, String> {
+    let Some(body) = &func.body else {
+        return Err("has no body".to_string());
+    };
+    let Some((last, stmts)) = body.stmts.split_last() else {
+        return Err("has an empty body".to_string());
+    };
+    let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = last else {
+        return Err("does not end by returning its output".to_string());
+    };
+
+    let Some(first_render) = stmts.iter().position(|stmt| !is_hook_stmt(stmt)) else {
+        return Ok(Fix {
+            description: "Convert to a stateful component".to_string(),
+            edits: vec![TextEdit {
+                span: arg.span().shrink_to_lo(),
+                replacement: "() => ".to_string(),
+            }],
+        });
+    };
+
+    let first = stmts[first_render].span();
+    let indent = source.indent_at(first.lo);
+    let unit = indent_unit(source.indent_at(func.span.lo), indent);
+    let mut setup = String::new();
+    let mut edits = vec![];
+    let mut moved = vec![];
+
+    // Hooks declared between render statements move up into setup, unless they use what those declare
+    for (index, stmt) in stmts.iter().enumerate().skip(first_render) {
+        if !is_hook_stmt(stmt) {
+            continue;
+        }
+        let render = stmts[first_render..index].iter().filter(|stmt| !is_hook_stmt(stmt));
+        if let Some(name) = render_dependency(render, stmt) {
+            return Err(format!(
+                "calls a hook that uses `{}`, which is computed while rendering",
+                name
+            ));
+        }
+
+        let Some(text) = source.slice(stmt.span()) else {
+            return Err("could not read the source of a hook call".to_string());
+        };
+        setup.push_str(text);
+        setup.push('\n');
+        setup.push_str(indent);
+        let span = source.whole_lines(stmt.span());
+        moved.push(span);
+        edits.push(TextEdit {
+            span,
+            replacement: String::new(),
+        });
+    }
+
+    // The statements left in render move one level deeper
+    let render = stmts[first_render..].iter().chain([last]);
+    for (pos, _) in source.line_starts(Span::new(first.lo, last.span().hi), render) {
+        if !moved.iter().any(|span| span.lo <= pos && pos < span.hi) {
+            edits.push(TextEdit {
+                span: Span::new(pos, pos),
+                replacement: unit.to_string(),
+            });
+        }
+    }
+
+    edits.push(TextEdit {
+        span: first.shrink_to_lo(),
+        replacement: format!("{}return () => {{\n{}{}", setup, indent, unit),
+    });
+    edits.push(TextEdit {
+        span: last.span().shrink_to_hi(),
+        replacement: format!("\n{}}};", indent),
+    });
+
+    Ok(Fix {
+        description: "Convert to a stateful component".to_string(),
+        edits,
+    })
+}
+
+/// Turn a stateful component without hooks into a stateless one by inlining its render function.
+/// Returns why the component can not be converted otherwise.
+pub fn to_stateless(func: &Function, source: SourceText) -> Result<Fix, String> {
+    let Some(body) = &func.body else {
+        return Err("has no body".to_string());
+    };
+    let Some((last, setup)) = body.stmts.split_last() else {
+        return Err("has an empty body".to_string());
+    };
+    let Stmt::Return(ret @ ReturnStmt { arg: Some(arg), .. }) = last else {
+        return Err("does not end by returning a render function".to_string());
+    };
+    let Expr::Arrow(render) = &**arg else {
+        return Err("does not end by returning a render function".to_string());
+    };
+    if !render.params.is_empty() || render.is_async || render.is_generator {
+        return Err("has a render function that takes parameters or is async".to_string());
+    }
+
+    // A stateless component runs its whole body on every render, where hooks are not allowed
+    let mut hooks = HookFinder { found: None };
+    for stmt in setup {
+        stmt.visit_with(&mut hooks);
+    }
+    if let Some(hook) = hooks.found {
+        return Err(format!("calls `{}` in setup, hooks can not run on every render", hook));
+    }
+
+    let edits = match &*render.body {
+        BlockStmtOrExpr::Expr(expr) => vec![TextEdit {
+            span: Span::new(render.span.lo, expr.span().lo),
+            replacement: String::new(),
+        }],
+        // The render function of an arrow component is its body, the block becomes the component body
+        BlockStmtOrExpr::BlockStmt(block) if ret.span.is_dummy() => vec![TextEdit {
+            span: Span::new(render.span.lo, block.span.lo),
+            replacement: String::new(),
+        }],
+        BlockStmtOrExpr::BlockStmt(block) => {
+            // Inlined declarations share the scope of the setup declarations
+            let setup_names = declared_names(setup);
+            let render_names = declared_names(&block.stmts);
+            if let Some(name) = render_names.iter().filter(|name| setup_names.contains(*name)).min() {
+                return Err(format!("declares `{}` both in setup and in its render function", name));
+            }
+            let mut edits = vec![
+                TextEdit {
+                    span: source.whole_lines(Span::new(ret.span.lo, block.span.lo + BytePos(1))),
+                    replacement: String::new(),
+                },
+                TextEdit {
+                    span: source.whole_lines(Span::new(block.span.hi - BytePos(1), ret.span.hi)),
+                    replacement: String::new(),
+                },
+            ];
+
+            // The statements of the render function move one level up
+            if let Some(inner) = block.stmts.last() {
+                let unit = indent_unit(
+                    source.indent_at(ret.span.lo),
+                    source.indent_at(block.stmts[0].span().lo),
+                );
+                for (pos, line) in source.line_starts(Span::new(block.span.lo, inner.span().hi), &block.stmts) {
+                    if line.starts_with(unit) {
+                        edits.push(TextEdit {
+                            span: Span::new(pos, pos + BytePos(unit.len() as u32)),
+                            replacement: String::new(),
+                        });
+                    }
+                }
+            }
+            edits
+        }
+    };
+
+    Ok(Fix {
+        description: "Convert to a stateless component".to_string(),
+        edits,
+    })
+}
+
+/// The indentation one level deeper than `outer`, as used between the function and its body
+pub(crate) fn indent_unit<'a>(outer: &str, body: &'a str) -> &'a str {
+    match body.strip_prefix(outer) {
+        Some(unit) if !unit.is_empty() => unit,
+        _ if body.starts_with('\t') => "\t",
+        _ => "    ",
+    }
+}
+
+/// Inline a `const` read of props or state declared in setup into the closures that use it,
+/// so the value is read when rendering instead of once when the component is created
+pub fn move_read_into_render(func: &Function, source: SourceText, pos: BytePos) -> Option<Fix> {
+    let stmt = setup_stmts(func).find(|stmt| stmt.span().lo <= pos && pos <= stmt.span().hi)?;
+    let Stmt::Decl(Decl::Var(var_decl)) = stmt else {
+        return None;
+    };
+    if var_decl.kind != VarDeclKind::Const || var_decl.decls.len() != 1 || is_hook_stmt(stmt) {
+        return None;
+    }
+    let decl = &var_decl.decls[0];
+    let (Pat::Ident(binding), Some(init)) = (&decl.name, decl.init.as_deref()) else {
+        return None;
+    };
+
+    let bindings = ReactiveBindings::collect(func);
+    let mut reads = ReactiveReadFinder {
+        bindings: &bindings,
+        found: false,
+    };
+    init.visit_with(&mut reads);
+    if !reads.found {
+        return None;
+    }
+
+    let mut references = ReferenceCollector {
+        sym: binding.id.sym.clone(),
+        declaration: binding.id.span,
+        references: vec![],
+        depth: 0,
+        unsupported: false,
+    };
+    func.body.visit_with(&mut references);
+    // Uses during setup would stay stale, and shadowed names can not be inlined safely
+    if references.unsupported || references.references.is_empty() {
+        return None;
+    }
+
+    let text = source.slice(init.span())?;
+    let inlined = match init {
+        Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) | Expr::OptChain(_) | Expr::Paren(_) | Expr::Lit(_) => {
+            text.to_string()
+        }
+        _ => format!("({})", text),
+    };
+
+    let mut edits = vec![TextEdit {
+        span: source.whole_lines(stmt.span()),
+        replacement: String::new(),
+    }];
+    for (span, shorthand) in references.references {
+        edits.push(TextEdit {
+            span,
+            replacement: if shorthand {
+                format!("{}: {}", binding.id.sym, inlined)
+            } else {
+                inlined.clone()
+            },
+        });
+    }
+
+    Some(Fix {
+        description: format!("Move the read of `{}` into render", binding.id.sym),
+        edits,
+    })
+}
+
+/// The first name a statement reads that the given render statements declare.
+/// Such a statement can't move into setup, which runs before them.
+pub(crate) fn render_dependency<'a>(render: impl IntoIterator<Item = &'a Stmt>, stmt: &Stmt) -> Option<String> {
+    let render_names = declared_names(render);
+    let mut used = IdentNames::default();
+    stmt.visit_with(&mut used);
+    used.names.into_iter().filter(|name| render_names.contains(name)).min()
+}
+
+fn declared_names<'a>(stmts: impl IntoIterator<Item = &'a Stmt>) -> HashSet<String> {
+    let mut collector = BindingNames::default();
+    for stmt in stmts {
+        match stmt {
+            Stmt::Decl(Decl::Var(var_decl)) => {
+                for decl in &var_decl.decls {
+                    decl.name.visit_with(&mut collector);
+                }
+            }
+            Stmt::Decl(Decl::Fn(fn_decl)) => {
+                collector.names.insert(fn_decl.ident.sym.to_string());
+            }
+            Stmt::Decl(Decl::Class(class_decl)) => {
+                collector.names.insert(class_decl.ident.sym.to_string());
+            }
+            _ => {}
+        }
+    }
+    collector.names
+}
+
+struct ComponentFinder {
+    components: Vec<ComponentDecl>,
+}
+
+impl ComponentFinder {
+    fn push(&mut self, name: String, span: Span, func: Function) {
+        if let Some(kind) = RaskComponentTransform::classify(&func) {
+            self.components.push(ComponentDecl { name, kind, span, func });
+        }
+    }
+}
+
+impl Visit for ComponentFinder {
+    noop_visit_type!();
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.push(
+            fn_decl.ident.sym.to_string(),
+            fn_decl.function.span,
+            (*fn_decl.function).clone(),
+        );
+        fn_decl.visit_children_with(self);
+    }
+
+    fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+        if let (Pat::Ident(binding), Some(Expr::Arrow(arrow))) = (&decl.name, decl.init.as_deref()) {
+            self.push(
+                binding.id.sym.to_string(),
+                decl.span,
+                RaskComponentTransform::arrow_component_function(arrow),
+            );
+        }
+        decl.visit_children_with(self);
+    }
+
+    fn visit_export_default_decl(&mut self, export: &ExportDefaultDecl) {
+        if let DefaultDecl::Fn(fn_expr) = &export.decl {
+            let name = fn_expr
+                .ident
+                .as_ref()
+                .map(|ident| ident.sym.to_string())
+                .unwrap_or_else(|| "default".to_string());
+            self.push(name, export.span, (*fn_expr.function).clone());
+        }
+        export.visit_children_with(self);
+    }
+
+    fn visit_export_default_expr(&mut self, export: &ExportDefaultExpr) {
+        if let Expr::Arrow(arrow) = &*export.expr {
+            self.push(
+                "default".to_string(),
+                export.span,
+                RaskComponentTransform::arrow_component_function(arrow),
+            );
+        }
+        export.visit_children_with(self);
+    }
+}
+
+struct HookFinder {
+    found: Option<String>,
+}
+
+impl Visit for HookFinder {
+    noop_visit_type!();
+
+    // Hooks in handlers are reported by the hook placement rule, they don't run in setup
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(name) = hook_call_name(call) {
+            self.found.get_or_insert(name);
+        }
+        call.visit_children_with(self);
+    }
+}
+
+struct ReactiveReadFinder<'a> {
+    bindings: &'a ReactiveBindings,
+    found: bool,
+}
+
+impl Visit for ReactiveReadFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let Some(root) = member_root(&member.obj) {
+            if self.bindings.get(root).is_some() {
+                self.found = true;
+            }
+        }
+        member.visit_children_with(self);
+    }
+}
+
+/// Collects the references to a setup declaration, with whether they are shorthand properties
+struct ReferenceCollector {
+    sym: Atom,
+    declaration: Span,
+    references: Vec<(Span, bool)>,
+    /// Depth of nested closures, references at depth zero run during setup
+    depth: usize,
+    unsupported: bool,
+}
+
+impl ReferenceCollector {
+    fn reference(&mut self, span: Span, shorthand: bool) {
+        if self.depth == 0 {
+            self.unsupported = true;
+        }
+        self.references.push((span, shorthand));
+    }
+}
+
+impl Visit for ReferenceCollector {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, func: &Function) {
+        self.depth += 1;
+        func.visit_children_with(self);
+        self.depth -= 1;
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.depth += 1;
+        arrow.visit_children_with(self);
+        self.depth -= 1;
+    }
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        if binding.id.sym == self.sym && binding.id.span != self.declaration {
+            self.unsupported = true;
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.sym == self.sym && ident.span != self.declaration {
+            self.reference(ident.span, false);
+        }
+    }
+
+    fn visit_prop(&mut self, prop: &Prop) {
+        match prop {
+            Prop::Shorthand(ident) if ident.sym == self.sym => self.reference(ident.span, true),
+            _ => prop.visit_children_with(self),
+        }
+    }
+
+    fn visit_jsx_element_name(&mut self, name: &JSXElementName) {
+        if matches!(name, JSXElementName::Ident(ident) if ident.sym == self.sym) {
+            self.unsupported = true;
+        }
+        name.visit_children_with(self);
+    }
+}
+
+/// Spans of template literals, whose lines are part of the string and must not be re-indented
+#[derive(Default)]
+struct TemplateSpans {
+    spans: Vec<Span>,
+}
+
+impl Visit for TemplateSpans {
+    noop_visit_type!();
+
+    fn visit_tpl(&mut self, tpl: &Tpl) {
+        self.spans.push(tpl.span);
+        tpl.visit_children_with(self);
+    }
+}
+
+/// Names of every identifier referenced, to tell whether a statement depends on a declaration
+#[derive(Default)]
+struct IdentNames {
+    names: HashSet<String>,
+}
+
+impl Visit for IdentNames {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.to_string());
+    }
+}
+
+#[derive(Default)]
+struct BindingNames {
+    names: HashSet<String>,
+}
+
+impl Visit for BindingNames {
+    noop_visit_type!();
+
+    // Default values are expressions, not declarations
+    fn visit_expr(&mut self, _: &Expr) {}
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.names.insert(binding.id.sym.to_string());
+    }
+}
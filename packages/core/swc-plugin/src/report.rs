@@ -0,0 +1,199 @@
+use serde::Serialize;
+use swc_core::common::{SourceMap, Span};
+
+use crate::compile::PARSE_ERROR_RULE;
+use crate::diagnostics::{Diagnostic, Fix, Severity};
+use crate::lint::LintedFile;
+use crate::location::Location;
+use crate::rules::RULES;
+
+/// Version of the JSON report format, bumped on breaking changes
+pub const JSON_REPORT_VERSION: u32 = 1;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    files: Vec<JsonFile<'a>>,
+    summary: JsonSummary,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    path: String,
+    diagnostics: Vec<JsonDiagnostic<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    rule: &'static str,
+    severity: &'static str,
+    message: &'a str,
+    span: Option<Location>,
+    suggestion: Option<&'a str>,
+    fix: Option<JsonFix<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFix<'a> {
+    description: &'a str,
+    edits: Vec<JsonEdit<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEdit<'a> {
+    span: Option<Location>,
+    replacement: &'a str,
+}
+
+impl<'a> JsonFix<'a> {
+    fn new(cm: &SourceMap, fix: &'a Fix) -> Self {
+        JsonFix {
+            description: &fix.description,
+            edits: fix
+                .edits
+                .iter()
+                .map(|edit| JsonEdit {
+                    span: Location::from_span(cm, edit.span),
+                    replacement: &edit.replacement,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSummary {
+    files: usize,
+    errors: usize,
+    warnings: usize,
+}
+
+/// Serialize the diagnostics of the linted files into the stable JSON report format
+pub fn to_json(cm: &SourceMap, files: &[LintedFile]) -> String {
+    let diagnostics = || files.iter().flat_map(|file| &file.diagnostics);
+    let count = |severity: Severity| diagnostics().filter(|diagnostic| diagnostic.severity == severity).count();
+
+    let report = JsonReport {
+        version: JSON_REPORT_VERSION,
+        files: files
+            .iter()
+            .map(|file| JsonFile {
+                path: file.path.display().to_string(),
+                diagnostics: file
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| JsonDiagnostic {
+                        rule: diagnostic.rule,
+                        severity: severity_name(diagnostic.severity),
+                        message: &diagnostic.message,
+                        span: Location::from_span(cm, diagnostic.span),
+                        suggestion: diagnostic.suggestion.as_deref(),
+                        fix: diagnostic.fix.as_ref().map(|fix| JsonFix::new(cm, fix)),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        summary: JsonSummary {
+            files: files.len(),
+            errors: count(Severity::Error),
+            warnings: count(Severity::Warning),
+        },
+    };
+
+    serde_json::to_string_pretty(&report).expect("JSON report serializes")
+}
+
+/// Serialize the diagnostics of the linted files into a SARIF 2.1.0 log
+pub fn to_sarif(cm: &SourceMap, files: &[LintedFile]) -> String {
+    let rules: Vec<_> = RULES
+        .iter()
+        .map(|rule| (rule.id, rule.description))
+        .chain([(PARSE_ERROR_RULE, "The file could not be parsed")])
+        .map(|(id, description)| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = files
+        .iter()
+        .flat_map(|file| file.diagnostics.iter().map(move |diagnostic| sarif_result(cm, file, diagnostic)))
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rask-lint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    });
+
+    serde_json::to_string_pretty(&log).expect("SARIF log serializes")
+}
+
+fn sarif_region(cm: &SourceMap, span: Span) -> Option<serde_json::Value> {
+    let location = Location::from_span(cm, span)?;
+    Some(serde_json::json!({
+        "startLine": location.start.line,
+        "startColumn": location.start.column,
+        "endLine": location.end.line,
+        "endColumn": location.end.column,
+    }))
+}
+
+fn sarif_result(cm: &SourceMap, file: &LintedFile, diagnostic: &Diagnostic) -> serde_json::Value {
+    let artifact_location = serde_json::json!({ "uri": file.path.display().to_string().replace('\\', "/") });
+    let mut physical_location = serde_json::json!({ "artifactLocation": artifact_location });
+    if let Some(region) = sarif_region(cm, diagnostic.span) {
+        physical_location["region"] = region;
+    }
+
+    let mut result = serde_json::json!({
+        "ruleId": diagnostic.rule,
+        "level": severity_name(diagnostic.severity),
+        "message": { "text": diagnostic.message },
+        "locations": [{ "physicalLocation": physical_location }],
+    });
+    if let Some(suggestion) = &diagnostic.suggestion {
+        result["properties"] = serde_json::json!({ "suggestion": suggestion });
+    }
+    if let Some(fix) = &diagnostic.fix {
+        let replacements: Vec<_> = fix
+            .edits
+            .iter()
+            .filter_map(|edit| {
+                Some(serde_json::json!({
+                    "deletedRegion": sarif_region(cm, edit.span)?,
+                    "insertedContent": { "text": edit.replacement },
+                }))
+            })
+            .collect();
+        result["fixes"] = serde_json::json!([{
+            "description": { "text": fix.description },
+            "artifactChanges": [{
+                "artifactLocation": artifact_location,
+                "replacements": replacements,
+            }],
+        }]);
+    }
+    result
+}
@@ -0,0 +1,334 @@
+use serde::Serialize;
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_name, unwrap_expr};
+use crate::diagnostics::Diagnostic;
+use crate::location::Location;
+
+pub const DUPLICATE_RULE: &str = "duplicate-route";
+pub const INVALID_RULE: &str = "invalid-route";
+pub const CONFLICT_RULE: &str = "conflicting-route";
+
+/// A route of a `useRouter` config, `user: "/users/:id?tab"`
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteManifest {
+    pub name: String,
+    pub pattern: String,
+    pub params: Vec<String>,
+    /// Query parameters declared after `?`
+    pub queries: Vec<String>,
+    /// The component whose setup creates the router
+    pub component: Option<String>,
+    #[serde(skip)]
+    pub span: Span,
+    pub location: Option<Location>,
+}
+
+/// A path segment of a route pattern
+#[derive(PartialEq, Eq)]
+enum Segment<'a> {
+    Static(&'a str),
+    Param,
+}
+
+/// Validate the route configs given to `useRouter` and describe their routes.
+/// Configs are read when passed inline or through a module level `const`, other configs are only known at runtime.
+pub fn check(module: &Module, diagnostics: &mut Vec<Diagnostic>) -> Vec<RouteManifest> {
+    let mut finder = RouterFinder {
+        module,
+        component: None,
+        routes: vec![],
+        diagnostics,
+    };
+    module.visit_with(&mut finder);
+    finder.routes
+}
+
+struct RouterFinder<'a> {
+    module: &'a Module,
+    component: Option<String>,
+    routes: Vec<RouteManifest>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl<'a> RouterFinder<'a> {
+    /// Visit a function, as the owner of the routers created in it when it is named like a component
+    fn visit_owner<N: VisitWith<Self>>(&mut self, name: &str, node: &N) {
+        let is_component = name.starts_with(|c: char| c.is_ascii_uppercase());
+        let previous = if is_component {
+            self.component.replace(name.to_string())
+        } else {
+            self.component.clone()
+        };
+        node.visit_children_with(self);
+        self.component = previous;
+    }
+
+    /// The config object, inline or declared as a module level `const`
+    fn config<'m>(&self, arg: &'m Expr) -> Option<&'m ObjectLit>
+    where
+        'a: 'm,
+    {
+        match unwrap_expr(arg) {
+            Expr::Object(object) => Some(object),
+            Expr::Ident(ident) => self.module_const(ident),
+            _ => None,
+        }
+    }
+
+    fn module_const(&self, ident: &Ident) -> Option<&'a ObjectLit> {
+        self.module.body.iter().find_map(|item| {
+            let var = match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(var), ..
+                })) => var,
+                _ => return None,
+            };
+            if var.kind != VarDeclKind::Const {
+                return None;
+            }
+            var.decls
+                .iter()
+                .find_map(|declarator| match (&declarator.name, &declarator.init) {
+                    (Pat::Ident(name), Some(init)) if name.id.to_id() == ident.to_id() => match unwrap_expr(init) {
+                        Expr::Object(object) => Some(object),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+        })
+    }
+
+    fn check_config(&mut self, config: &ObjectLit) {
+        // Patterns are borrowed by their segments, so they are collected first
+        let mut entries = vec![];
+
+        for prop in &config.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let Prop::KeyValue(KeyValueProp { key, value }) = &**prop else {
+                continue;
+            };
+            let name = match key {
+                PropName::Ident(ident) => ident.sym.to_string(),
+                PropName::Str(str) => str.value.to_string_lossy().into_owned(),
+                _ => continue,
+            };
+            let pattern = match unwrap_expr(value) {
+                Expr::Lit(Lit::Str(str)) => str.value.to_string_lossy().into_owned(),
+                Expr::Tpl(tpl) if tpl.exprs.is_empty() => match tpl.quasis.first() {
+                    Some(quasi) => quasi.raw.to_string(),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            entries.push((name, pattern, value.span()));
+        }
+
+        let mut routes: Vec<(RouteManifest, Vec<Segment>)> = vec![];
+        for (name, pattern, span) in &entries {
+            if routes.iter().any(|(route, _)| route.name == *name) {
+                self.diagnostics.push(Diagnostic::error(
+                    DUPLICATE_RULE,
+                    *span,
+                    format!(
+                        "The route `{}` is declared more than once, only the last declaration is used",
+                        name
+                    ),
+                ));
+                continue;
+            }
+            let Some((segments, params, queries)) = self.parse(pattern, *span) else {
+                continue;
+            };
+
+            for (other, other_segments) in &routes {
+                if other_segments == &segments {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            DUPLICATE_RULE,
+                            *span,
+                            format!(
+                                "`{}` matches the same paths as `{}`, \"{}\" and \"{}\"",
+                                name, other.name, pattern, other.pattern
+                            ),
+                        )
+                        .with_suggestion(format!("Remove one of the routes or give `{}` a distinct path", name)),
+                    );
+                } else if let Some(path) = shared_path(&segments, other_segments) {
+                    self.diagnostics.push(
+                        Diagnostic::warning(
+                            CONFLICT_RULE,
+                            *span,
+                            format!(
+                                "`{}` (\"{}\") overlaps `{}` (\"{}\") and neither is more specific, both match \"{}\"",
+                                name, pattern, other.name, other.pattern, path
+                            ),
+                        )
+                        .with_suggestion("Add a static segment so every path matches a single route"),
+                    );
+                }
+            }
+
+            routes.push((
+                RouteManifest {
+                    name: name.clone(),
+                    pattern: pattern.clone(),
+                    params,
+                    queries,
+                    component: self.component.clone(),
+                    span: *span,
+                    location: None,
+                },
+                segments,
+            ));
+        }
+
+        self.routes.extend(routes.into_iter().map(|(route, _)| route));
+    }
+
+    /// Split a pattern into path segments, param names and query names, reporting invalid patterns
+    fn parse<'p>(&mut self, pattern: &'p str, span: Span) -> Option<(Vec<Segment<'p>>, Vec<String>, Vec<String>)> {
+        let (path, query) = pattern.split_once('?').unwrap_or((pattern, ""));
+
+        if !path.starts_with('/') {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    INVALID_RULE,
+                    span,
+                    format!("The route pattern \"{}\" must start with `/`", pattern),
+                )
+                .with_suggestion(format!("Use \"/{}\"", pattern)),
+            );
+            return None;
+        }
+
+        let mut segments = vec![];
+        let mut params: Vec<String> = vec![];
+        let mut valid = true;
+
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            let Some(param) = segment.strip_prefix(':') else {
+                if segment.contains(':') {
+                    valid = false;
+                    self.diagnostics.push(Diagnostic::error(
+                        INVALID_RULE,
+                        span,
+                        format!(
+                            "The segment \"{}\" of \"{}\" has a param that is not the whole segment, params are written `/:name`",
+                            segment, pattern
+                        ),
+                    ));
+                }
+                segments.push(Segment::Static(segment));
+                continue;
+            };
+
+            if !is_param_name(param) {
+                valid = false;
+                self.diagnostics.push(Diagnostic::error(
+                    INVALID_RULE,
+                    span,
+                    format!(
+                        "`:{}` in \"{}\" is not a valid param name, names are identifiers like `:id`",
+                        param, pattern
+                    ),
+                ));
+            } else if params.iter().any(|existing| existing == param) {
+                valid = false;
+                self.diagnostics.push(Diagnostic::error(
+                    INVALID_RULE,
+                    span,
+                    format!("The param `:{}` appears more than once in \"{}\"", param, pattern),
+                ));
+            } else {
+                params.push(param.to_string());
+            }
+            segments.push(Segment::Param);
+        }
+
+        let queries = query
+            .split('&')
+            .filter(|query| !query.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        valid.then_some((segments, params, queries))
+    }
+}
+
+impl Visit for RouterFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.visit_owner(&fn_decl.ident.sym, fn_decl);
+    }
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        let is_function = declarator
+            .init
+            .as_deref()
+            .is_some_and(|init| matches!(unwrap_expr(init), Expr::Arrow(_) | Expr::Fn(_)));
+        match &declarator.name {
+            Pat::Ident(name) if is_function => self.visit_owner(&name.id.sym, declarator),
+            _ => declarator.visit_children_with(self),
+        }
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        call.visit_children_with(self);
+
+        if callee_name(call) != Some("useRouter") {
+            return;
+        }
+        let Some(config) = call.args.first().and_then(|arg| self.config(&arg.expr)) else {
+            return;
+        };
+        self.check_config(config);
+    }
+}
+
+fn is_param_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// A path matched by both patterns, when they overlap and neither is more specific.
+/// `/users/new` is more specific than `/users/:id`, while `/users/:id` and `/:section/new` both match `/users/new`.
+fn shared_path(a: &[Segment], b: &[Segment]) -> Option<String> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let mut path = String::new();
+    let (mut a_specific, mut b_specific) = (false, false);
+    for (a, b) in a.iter().zip(b) {
+        let segment = match (a, b) {
+            (Segment::Static(a), Segment::Static(b)) if a != b => return None,
+            (Segment::Static(segment), Segment::Static(_)) => *segment,
+            (Segment::Static(segment), Segment::Param) => {
+                a_specific = true;
+                *segment
+            }
+            (Segment::Param, Segment::Static(segment)) => {
+                b_specific = true;
+                *segment
+            }
+            (Segment::Param, Segment::Param) => "value",
+        };
+        path.push('/');
+        path.push_str(segment);
+    }
+    if !(a_specific && b_specific) {
+        return None;
+    }
+    Some(path)
+}
@@ -0,0 +1,97 @@
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{hook_call_name, is_hook_stmt, render_closures};
+use crate::diagnostics::{Diagnostic, Fix, TextEdit};
+
+pub const RULE: &str = "hook-in-render";
+
+/// Flag hooks called while rendering, where the runtime only allows them during setup
+pub fn check(func: &Function, is_stateful: bool, diagnostics: &mut Vec<Diagnostic>) {
+    let mut visitor = HookCallVisitor { calls: vec![] };
+    if is_stateful {
+        for render in render_closures(func) {
+            render.body.visit_with(&mut visitor);
+        }
+    } else {
+        func.body.visit_with(&mut visitor);
+    }
+
+    if visitor.calls.is_empty() {
+        return;
+    }
+
+    // A stateless component with hooks becomes stateful by returning its output as a render function
+    let fix = if is_stateful { None } else { stateful_conversion_fix(func) };
+
+    for (index, (span, name)) in visitor.calls.into_iter().enumerate() {
+        let diagnostic = if is_stateful {
+            Diagnostic::error(
+                RULE,
+                span,
+                format!("`{}` is called during render, but hooks can only be called in the component setup", name),
+            )
+            .with_suggestion(format!("Call `{}` in the setup, before `return () => ...`", name))
+        } else {
+            Diagnostic::error(
+                RULE,
+                span,
+                format!(
+                    "`{}` is called in a stateless component, which runs its whole body on every render",
+                    name
+                ),
+            )
+            .with_suggestion("Make the component stateful by returning a render function: `return () => ...`")
+        };
+
+        // The conversion fixes every hook of the component at once, so only attach it once
+        match (&fix, index) {
+            (Some(fix), 0) => diagnostics.push(diagnostic.with_fix(fix.clone())),
+            _ => diagnostics.push(diagnostic),
+        }
+    }
+}
+
+/// Wrap the returned output of a stateless component in `() => ...` when the body is only hooks and a return
+fn stateful_conversion_fix(func: &Function) -> Option<Fix> {
+    let body = func.body.as_ref()?;
+    let (last, hooks) = body.stmts.split_last()?;
+
+    if !hooks.iter().all(is_hook_stmt) {
+        return None;
+    }
+    let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = last else {
+        return None;
+    };
+
+    Some(Fix {
+        description: "Convert the stateless component into a stateful component".to_string(),
+        edits: vec![TextEdit {
+            span: arg.span().shrink_to_lo(),
+            replacement: "() => ".to_string(),
+        }],
+    })
+}
+
+struct HookCallVisitor {
+    calls: Vec<(Span, String)>,
+}
+
+impl Visit for HookCallVisitor {
+    noop_visit_type!();
+
+    // Nested functions are handlers or callbacks and don't run as part of render
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(name) = hook_call_name(call) {
+            self.calls.push((call.span, name));
+        }
+        call.visit_children_with(self);
+    }
+}
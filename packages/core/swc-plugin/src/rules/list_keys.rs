@@ -0,0 +1,220 @@
+use swc_core::common::Spanned;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_method, callee_name, render_closures, unwrap_expr};
+use crate::diagnostics::Diagnostic;
+
+pub const MISSING_KEY_RULE: &str = "missing-key";
+pub const INDEX_KEY_RULE: &str = "index-key";
+
+/// Check list-producing `.map(...)` callbacks in render output for missing or index-based keys
+pub fn check(func: &Function, is_stateful: bool, diagnostics: &mut Vec<Diagnostic>) {
+    let mut visitor = ListKeyVisitor { diagnostics };
+    if is_stateful {
+        for render in render_closures(func) {
+            render.body.visit_with(&mut visitor);
+        }
+    } else {
+        func.body.visit_with(&mut visitor);
+    }
+}
+
+/// Position of the `key` argument for each VNode factory emitted by the Inferno JSX transform
+fn key_arg_index(factory: &str) -> Option<usize> {
+    match factory {
+        "createVNode" => Some(6),
+        "createComponentVNode" => Some(3),
+        "createFragment" => Some(2),
+        "createTextVNode" => Some(1),
+        _ => None,
+    }
+}
+
+/// The key given to a VNode, `Some(None)` when it is a VNode without a key
+fn vnode_key(expr: &Expr) -> Option<Option<&Expr>> {
+    match unwrap_expr(expr) {
+        Expr::Call(call) => {
+            let index = key_arg_index(callee_name(call)?)?;
+            let key = call.args.get(index).map(|arg| &*arg.expr).filter(|key| {
+                !matches!(unwrap_expr(key), Expr::Lit(Lit::Null(_)))
+                    && !matches!(unwrap_expr(key), Expr::Ident(ident) if &*ident.sym == "undefined")
+            });
+            Some(key)
+        }
+        Expr::JSXElement(element) => {
+            let key_attr = element.opening.attrs.iter().find_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(attr) if matches!(&attr.name, JSXAttrName::Ident(name) if &*name.sym == "key") => {
+                    Some(attr)
+                }
+                _ => None,
+            });
+            let has_spread = element
+                .opening
+                .attrs
+                .iter()
+                .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)));
+
+            match key_attr.and_then(|attr| attr.value.as_ref()) {
+                Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                    expr: JSXExpr::Expr(expr),
+                    ..
+                })) => Some(Some(&**expr)),
+                // A string literal key is a valid key
+                Some(_) => None,
+                // Spread props might carry the key
+                None if has_spread => None,
+                None => Some(None),
+            }
+        }
+        // Fragments shorthand can not take a key
+        Expr::JSXFragment(_) => Some(None),
+        _ => None,
+    }
+}
+
+/// Collect the expressions a list callback can return
+fn returned_exprs<'a>(callback: &'a Expr, returns: &mut Vec<&'a Expr>) {
+    match unwrap_expr(callback) {
+        Expr::Arrow(arrow) => match &*arrow.body {
+            BlockStmtOrExpr::Expr(expr) => branches(expr, returns),
+            BlockStmtOrExpr::BlockStmt(block) => block_returns(&block.stmts, returns),
+        },
+        Expr::Fn(fn_expr) => {
+            if let Some(body) = &fn_expr.function.body {
+                block_returns(&body.stmts, returns);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn block_returns<'a>(stmts: &'a [Stmt], returns: &mut Vec<&'a Expr>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Return(ReturnStmt { arg: Some(arg), .. }) => branches(arg, returns),
+            Stmt::If(if_stmt) => {
+                block_returns(std::slice::from_ref(&*if_stmt.cons), returns);
+                if let Some(alt) = &if_stmt.alt {
+                    block_returns(std::slice::from_ref(&**alt), returns);
+                }
+            }
+            Stmt::Block(block) => block_returns(&block.stmts, returns),
+            _ => {}
+        }
+    }
+}
+
+/// Split conditional and logical expressions into the values they can produce
+fn branches<'a>(expr: &'a Expr, returns: &mut Vec<&'a Expr>) {
+    match unwrap_expr(expr) {
+        Expr::Cond(cond) => {
+            branches(&cond.cons, returns);
+            branches(&cond.alt, returns);
+        }
+        Expr::Bin(bin) if matches!(bin.op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing) => {
+            branches(&bin.right, returns);
+        }
+        expr => returns.push(expr),
+    }
+}
+
+/// Index parameter of a `.map((item, index) => ...)` callback
+fn index_param(callback: &Expr) -> Option<Id> {
+    let pat = match unwrap_expr(callback) {
+        Expr::Arrow(arrow) => arrow.params.get(1)?,
+        Expr::Fn(fn_expr) => &fn_expr.function.params.get(1)?.pat,
+        _ => return None,
+    };
+    match pat {
+        Pat::Ident(binding) => Some(binding.id.to_id()),
+        _ => None,
+    }
+}
+
+struct ListKeyVisitor<'a> {
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl ListKeyVisitor<'_> {
+    fn check_map_callback(&mut self, callback: &Expr) {
+        let index = index_param(callback);
+        let mut returns = vec![];
+        returned_exprs(callback, &mut returns);
+
+        for returned in returns {
+            match vnode_key(returned) {
+                Some(None) => self.diagnostics.push(
+                    Diagnostic::warning(
+                        MISSING_KEY_RULE,
+                        returned.span(),
+                        "Elements returned from `.map(...)` need a `key`, otherwise items are reconciled by position and re-render or lose state when the list changes",
+                    )
+                    .with_suggestion("Add a `key` prop with a stable identifier of the item, e.g. `key={item.id}`"),
+                ),
+                Some(Some(key)) => {
+                    if let Some(index) = &index {
+                        if uses_ident(key, index) {
+                            self.diagnostics.push(
+                                Diagnostic::warning(
+                                    INDEX_KEY_RULE,
+                                    key.span(),
+                                    "The list index is used as `key`, so items are matched by position and re-render or lose state when the list is reordered",
+                                )
+                                .with_suggestion("Use a stable identifier of the item as `key`, e.g. `key={item.id}`"),
+                            );
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+impl Visit for ListKeyVisitor<'_> {
+    noop_visit_type!();
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some((_, "map")) = callee_method(call) {
+            if let Some(callback) = call.args.first() {
+                self.check_map_callback(&callback.expr);
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// Check if an expression reads the given binding
+fn uses_ident(expr: &Expr, id: &Id) -> bool {
+    struct UsesIdent<'a> {
+        id: &'a Id,
+        found: bool,
+    }
+
+    impl Visit for UsesIdent<'_> {
+        noop_visit_type!();
+
+        fn visit_ident(&mut self, ident: &Ident) {
+            if &ident.to_id() == self.id {
+                self.found = true;
+            }
+        }
+
+        // `item.index` reads a property, not the binding
+        fn visit_member_prop(&mut self, prop: &MemberProp) {
+            if let MemberProp::Computed(computed) = prop {
+                computed.visit_with(self);
+            }
+        }
+    }
+
+    let mut visitor = UsesIdent {
+        id,
+        found: false,
+    };
+    expr.visit_with(&mut visitor);
+    visitor.found
+}
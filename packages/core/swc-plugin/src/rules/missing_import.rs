@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_name, RUNTIME_FUNCTIONS, SETUP_HOOKS};
+use crate::diagnostics::{Diagnostic, Fix, TextEdit};
+
+pub const RULE: &str = "missing-import";
+
+/// Flag rask-ui functions that are called without being imported or declared in the module
+pub fn check(module: &Module, import_source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let mut collector = BindingCollector::default();
+    module.visit_with(&mut collector);
+
+    let mut missing: Vec<(&str, Span)> = vec![];
+    for (name, span) in &collector.calls {
+        if !collector.declared.contains(name.as_str()) && !missing.iter().any(|(missing, _)| *missing == name.as_str()) {
+            missing.push((name.as_str(), *span));
+        }
+    }
+
+    if missing.is_empty() {
+        return;
+    }
+
+    // One fix imports every missing function, attached to the first diagnostic
+    let names: Vec<&str> = missing.iter().map(|(name, _)| *name).collect();
+    let mut fix = Some(import_fix(module, import_source, &names));
+
+    for (name, span) in missing {
+        let mut diagnostic = Diagnostic::warning(
+            RULE,
+            span,
+            format!("`{}` is called but not imported from \"{}\"", name, import_source),
+        )
+        .with_suggestion(format!("Add `import {{ {} }} from \"{}\"`", name, import_source));
+        if let Some(fix) = fix.take() {
+            diagnostic = diagnostic.with_fix(fix);
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Add the names to an existing named import from the import source, or insert a new import
+fn import_fix(module: &Module, import_source: &str, names: &[&str]) -> Fix {
+    let description = format!("Import {} from \"{}\"", names.join(", "), import_source);
+
+    let existing = module.body.iter().find_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+            if &*import.src.value == import_source && !import.type_only =>
+        {
+            match import.specifiers.last() {
+                Some(ImportSpecifier::Named(last)) => Some(last.span),
+                _ => None,
+            }
+        }
+        _ => None,
+    });
+
+    let edit = match existing {
+        Some(last_specifier) => TextEdit {
+            span: last_specifier.shrink_to_hi(),
+            replacement: format!(", {}", names.join(", ")),
+        },
+        None => TextEdit {
+            span: module
+                .body
+                .first()
+                .map(|item| item.span())
+                .unwrap_or(module.span)
+                .shrink_to_lo(),
+            replacement: format!("import {{ {} }} from \"{}\";\n", names.join(", "), import_source),
+        },
+    };
+
+    Fix {
+        description,
+        edits: vec![edit],
+    }
+}
+
+#[derive(Default)]
+struct BindingCollector {
+    declared: HashSet<String>,
+    calls: Vec<(String, Span)>,
+}
+
+impl Visit for BindingCollector {
+    noop_visit_type!();
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.declared.insert(binding.id.sym.to_string());
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.declared.insert(fn_decl.ident.sym.to_string());
+        fn_decl.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.declared.insert(class_decl.ident.sym.to_string());
+        class_decl.visit_children_with(self);
+    }
+
+    fn visit_import_specifier(&mut self, specifier: &ImportSpecifier) {
+        let local = match specifier {
+            ImportSpecifier::Named(named) => &named.local,
+            ImportSpecifier::Default(default) => &default.local,
+            ImportSpecifier::Namespace(namespace) => &namespace.local,
+        };
+        self.declared.insert(local.sym.to_string());
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(name) = callee_name(call) {
+            if SETUP_HOOKS.contains(&name) || RUNTIME_FUNCTIONS.contains(&name) {
+                self.calls.push((name.to_string(), call.span));
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
@@ -0,0 +1,68 @@
+pub mod hook_placement;
+pub mod list_keys;
+pub mod missing_import;
+pub mod nested_component;
+pub mod render_mutation;
+pub mod stale_setup_read;
+pub mod this_usage;
+
+/// A rule the analysis can report, with a one line description for tool output
+pub struct RuleInfo {
+    pub id: &'static str,
+    pub description: &'static str,
+}
+
+pub const RULES: &[RuleInfo] = &[
+    RuleInfo {
+        id: stale_setup_read::RULE,
+        description: "Reactive values read during setup are only read once and never update",
+    },
+    RuleInfo {
+        id: render_mutation::RULE,
+        description: "Component state must not be written while the render function runs",
+    },
+    RuleInfo {
+        id: nested_component::RULE,
+        description: "Components declared inside a render function are recreated on every render",
+    },
+    RuleInfo {
+        id: list_keys::MISSING_KEY_RULE,
+        description: "Elements returned from `.map(...)` need a `key`",
+    },
+    RuleInfo {
+        id: list_keys::INDEX_KEY_RULE,
+        description: "The list index should not be used as `key`",
+    },
+    RuleInfo {
+        id: hook_placement::RULE,
+        description: "Hooks can only be called during setup, not while rendering",
+    },
+    RuleInfo {
+        id: this_usage::RULE,
+        description: "Components are functions, `this` does not refer to the component",
+    },
+    RuleInfo {
+        id: missing_import::RULE,
+        description: "rask-ui functions must be imported before they are called",
+    },
+    RuleInfo {
+        id: crate::async_setup::RULE,
+        description: "Hooks in an async setup must be called before its first `await`",
+    },
+    RuleInfo {
+        id: crate::routes::DUPLICATE_RULE,
+        description: "Every route of a `useRouter` config needs a distinct name and path",
+    },
+    RuleInfo {
+        id: crate::routes::INVALID_RULE,
+        description: "Route patterns start with `/` and name their params like `:id`",
+    },
+    RuleInfo {
+        id: crate::routes::CONFLICT_RULE,
+        description: "Overlapping routes of a `useRouter` config should have one that is more specific",
+    },
+    RuleInfo {
+        id: crate::server_actions::RULE,
+        description: "\"use server\" functions must be async and declared at module level",
+    },
+];
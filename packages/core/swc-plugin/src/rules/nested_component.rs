@@ -0,0 +1,21 @@
+use swc_core::ecma::ast::Ident;
+
+use crate::diagnostics::Diagnostic;
+
+pub const RULE: &str = "nested-component";
+
+/// Report a component declared inside another component's render function
+pub fn diagnostic(name: &Ident) -> Diagnostic {
+    Diagnostic::warning(
+        RULE,
+        name.span,
+        format!(
+            "`{}` is declared inside a render function, so it becomes a new component on every render and Inferno remounts it, losing its state",
+            name.sym
+        ),
+    )
+    .with_suggestion(format!(
+        "Move `{}` to module level or into the parent component's setup",
+        name.sym
+    ))
+}
@@ -0,0 +1,110 @@
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{
+    callee_method, callee_name, member_root, render_closures, unwrap_expr, ReactiveBindings, ReactiveKind,
+    MUTATING_METHODS,
+};
+use crate::diagnostics::Diagnostic;
+
+pub const RULE: &str = "render-mutation";
+
+/// Flag writes to component state that run synchronously while the render closure executes
+pub fn check(func: &Function, diagnostics: &mut Vec<Diagnostic>) {
+    let bindings = ReactiveBindings::collect(func);
+    if bindings.is_empty() {
+        return;
+    }
+
+    let mut visitor = RenderMutationVisitor {
+        bindings: &bindings,
+        diagnostics,
+    };
+    for render in render_closures(func) {
+        render.body.visit_with(&mut visitor);
+    }
+}
+
+struct RenderMutationVisitor<'a> {
+    bindings: &'a ReactiveBindings,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl RenderMutationVisitor<'_> {
+    /// Report the write if the target is rooted in a state binding
+    fn check_target(&mut self, span: Span, target: &Expr, action: &str) {
+        let Some(root) = member_root(target) else {
+            return;
+        };
+        let Some(kind @ (ReactiveKind::State | ReactiveKind::Context)) = self.bindings.get(root) else {
+            return;
+        };
+        self.diagnostics.push(
+            Diagnostic::error(
+                RULE,
+                span,
+                format!(
+                    "{} `{}` ({}) during render triggers a new render and can loop forever",
+                    action,
+                    root.sym,
+                    kind.describe()
+                ),
+            )
+            .with_suggestion("Move the write into an event handler, `useEffect` or the component setup"),
+        );
+    }
+}
+
+impl Visit for RenderMutationVisitor<'_> {
+    noop_visit_type!();
+
+    // Writes inside nested functions (event handlers, callbacks) do not run during render
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+    fn visit_getter_prop(&mut self, _: &GetterProp) {}
+    fn visit_setter_prop(&mut self, _: &SetterProp) {}
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left {
+            self.check_target(assign.span, &member.obj, "Assigning to");
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, update: &UpdateExpr) {
+        if let Expr::Member(member) = unwrap_expr(&update.arg) {
+            self.check_target(update.span, &member.obj, "Updating");
+        }
+        update.visit_children_with(self);
+    }
+
+    fn visit_unary_expr(&mut self, unary: &UnaryExpr) {
+        if unary.op == UnaryOp::Delete {
+            if let Expr::Member(member) = unwrap_expr(&unary.arg) {
+                self.check_target(unary.span, &member.obj, "Deleting from");
+            }
+        }
+        unary.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some((receiver, method)) = callee_method(call) {
+            if MUTATING_METHODS.contains(&method) {
+                self.check_target(call.span, receiver, &format!("Calling `.{}()` on", method));
+            }
+        }
+
+        // `assignState(state, { ... })` replaces the state wholesale
+        if matches!(callee_name(call), Some("assignState")) {
+            if let Some(first) = call.args.first() {
+                self.check_target(call.span, &first.expr, "Assigning to");
+            }
+        }
+
+        call.visit_children_with(self);
+    }
+}
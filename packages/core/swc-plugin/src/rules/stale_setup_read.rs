@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::analysis::{callee_method, callee_name, setup_stmts, unwrap_expr, ReactiveBindings, MUTATING_METHODS};
+use crate::diagnostics::{Diagnostic, Fix, TextEdit};
+
+pub const RULE: &str = "stale-setup-read";
+
+/// Warn about reactive values read in setup, which only runs once and never sees later updates
+pub fn check(func: &Function, diagnostics: &mut Vec<Diagnostic>) {
+    // Destructuring props in the signature reads every prop once during setup
+    if let Some(param) = func.params.first() {
+        if let Pat::Object(object_pat) = &param.pat {
+            let mut diagnostic = Diagnostic::warning(
+                RULE,
+                object_pat.span,
+                "props are destructured in the component signature, so the values are read once during setup and never update",
+            )
+            .with_suggestion("Accept `props` as a single parameter and read `props.<name>` inside the render function");
+            if let Some(fix) = destructured_props_fix(func, object_pat) {
+                diagnostic = diagnostic.with_fix(fix);
+            }
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    let bindings = ReactiveBindings::collect(func);
+    if bindings.is_empty() {
+        return;
+    }
+
+    let mut visitor = SetupReadVisitor {
+        bindings: &bindings,
+        diagnostics,
+    };
+    for stmt in setup_stmts(func) {
+        stmt.visit_with(&mut visitor);
+    }
+}
+
+struct SetupReadVisitor<'a> {
+    bindings: &'a ReactiveBindings,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl SetupReadVisitor<'_> {
+    fn report(&mut self, span: Span, ident: &Ident, action: &str) {
+        let Some(kind) = self.bindings.get(ident) else {
+            return;
+        };
+        self.diagnostics.push(
+            Diagnostic::warning(
+                RULE,
+                span,
+                format!(
+                    "{} `{}` ({}) during setup only happens once, so the value will never update",
+                    action,
+                    ident.sym,
+                    kind.describe()
+                ),
+            )
+            .with_suggestion(format!(
+                "Move the read of `{}` into the render function or compute it with `useDerived`",
+                ident.sym
+            )),
+        );
+    }
+}
+
+impl Visit for SetupReadVisitor<'_> {
+    noop_visit_type!();
+
+    // Reads inside closures run later (handlers, effects, derived callbacks) and are fine
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+    fn visit_getter_prop(&mut self, _: &GetterProp) {}
+    fn visit_setter_prop(&mut self, _: &SetterProp) {}
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let Expr::Ident(obj) = unwrap_expr(&member.obj) {
+            self.report(member.span, obj, "Reading");
+            // `items[props.index]` reads the computed key as well
+            member.prop.visit_with(self);
+            return;
+        }
+        member.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        // Seeding state from props is an intentional one-time read
+        if matches!(callee_name(call), Some("useState")) {
+            return;
+        }
+
+        if let Some((receiver, method)) = callee_method(call) {
+            // `props.onReady()` invokes a callback, it does not snapshot a value
+            if let Expr::Ident(obj) = unwrap_expr(receiver) {
+                if self.bindings.get(obj).is_some() {
+                    call.args.visit_with(self);
+                    return;
+                }
+            }
+            // `state.items.push(item)` mutates, it does not snapshot a value
+            if MUTATING_METHODS.contains(&method) {
+                call.args.visit_with(self);
+                return;
+            }
+        }
+
+        call.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        // Writing during setup is initialization, only the right hand side is a read
+        assign.right.visit_with(self);
+    }
+
+    fn visit_update_expr(&mut self, _: &UpdateExpr) {}
+
+    fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+        if let (Pat::Object(object_pat), Some(init)) = (&decl.name, &decl.init) {
+            if let Expr::Ident(obj) = unwrap_expr(init) {
+                self.report(object_pat.span, obj, "Destructuring");
+                return;
+            }
+        }
+        decl.visit_children_with(self);
+    }
+}
+
+/// Replace destructured props with a `props` parameter and rewrite every use to `props.<name>`
+pub(crate) fn destructured_props_fix(func: &Function, object_pat: &ObjectPat) -> Option<Fix> {
+    let mut renames = HashMap::new();
+    for prop in &object_pat.props {
+        match prop {
+            ObjectPatProp::KeyValue(KeyValuePatProp {
+                key: PropName::Ident(key),
+                value,
+            }) => {
+                let Pat::Ident(binding) = &**value else {
+                    return None;
+                };
+                renames.insert(binding.id.to_id(), key.sym.to_string());
+            }
+            ObjectPatProp::Assign(AssignPatProp { key, value: None, .. }) => {
+                renames.insert(key.id.to_id(), key.id.sym.to_string());
+            }
+            // Defaults and rest elements have no mechanical equivalent
+            _ => return None,
+        }
+    }
+
+    let param_end = object_pat
+        .type_ann
+        .as_ref()
+        .map(|type_ann| type_ann.span.lo)
+        .unwrap_or(object_pat.span.hi);
+
+    let mut rewriter = PropsRewriter {
+        renames: &renames,
+        edits: vec![TextEdit {
+            span: Span::new(object_pat.span.lo, param_end),
+            replacement: "props".to_string(),
+        }],
+        conflict: false,
+    };
+    func.body.visit_with(&mut rewriter);
+
+    if rewriter.conflict {
+        return None;
+    }
+
+    Some(Fix {
+        description: "Replace destructured props with `props` and read `props.<name>` where they are used".to_string(),
+        edits: rewriter.edits,
+    })
+}
+
+struct PropsRewriter<'a> {
+    renames: &'a HashMap<Id, String>,
+    edits: Vec<TextEdit>,
+    /// The body declares `props` or shadows a destructured name, so the rewrite is not safe
+    conflict: bool,
+}
+
+impl Visit for PropsRewriter<'_> {
+    noop_visit_type!();
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        if &*binding.id.sym == "props" || self.renames.contains_key(&binding.id.to_id()) {
+            self.conflict = true;
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if &*ident.sym == "props" {
+            self.conflict = true;
+        }
+        if let Some(key) = self.renames.get(&ident.to_id()) {
+            self.edits.push(TextEdit {
+                span: ident.span,
+                replacement: format!("props.{}", key),
+            });
+        }
+    }
+
+    fn visit_prop(&mut self, prop: &Prop) {
+        // `{ title }` has to keep its key when the value becomes `props.title`
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(key) = self.renames.get(&ident.to_id()) {
+                self.edits.push(TextEdit {
+                    span: ident.span,
+                    replacement: format!("{}: props.{}", ident.sym, key),
+                });
+                return;
+            }
+        }
+        prop.visit_children_with(self);
+    }
+
+    fn visit_jsx_element_name(&mut self, name: &JSXElementName) {
+        // Lowercase names are intrinsic elements, not references to a binding
+        if let JSXElementName::Ident(ident) = name {
+            if ident.sym.starts_with(|c: char| c.is_ascii_uppercase()) {
+                self.visit_ident(ident);
+            }
+        }
+    }
+}
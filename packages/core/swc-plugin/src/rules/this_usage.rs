@@ -0,0 +1,74 @@
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::diagnostics::{Diagnostic, Fix, TextEdit};
+
+pub const RULE: &str = "this-in-component";
+
+/// Flag `this` in a component body, including its arrow functions, which inherit the component's `this`
+pub fn check(func: &Function, diagnostics: &mut Vec<Diagnostic>) {
+    let props_name = match func.params.first().map(|param| &param.pat) {
+        Some(Pat::Ident(props)) => Some(props.id.sym.to_string()),
+        _ => None,
+    };
+
+    let mut visitor = ThisVisitor {
+        props_name: props_name.as_deref(),
+        diagnostics,
+    };
+    func.body.visit_with(&mut visitor);
+}
+
+struct ThisVisitor<'a> {
+    props_name: Option<&'a str>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Visit for ThisVisitor<'_> {
+    noop_visit_type!();
+
+    // Regular functions and classes bind their own `this`
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_class(&mut self, _: &Class) {}
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        let is_this_props = matches!(&*member.obj, Expr::This(_))
+            && matches!(&member.prop, MemberProp::Ident(prop) if &*prop.sym == "props");
+
+        if !is_this_props {
+            member.visit_children_with(self);
+            return;
+        }
+
+        let mut diagnostic = Diagnostic::warning(
+            RULE,
+            member.span,
+            "`this.props` does not exist in a Rask component, components are functions that receive props as their parameter",
+        )
+        .with_suggestion("Read from the component's `props` parameter instead");
+
+        if let Some(props_name) = self.props_name {
+            diagnostic = diagnostic.with_fix(Fix {
+                description: format!("Replace `this.props` with `{}`", props_name),
+                edits: vec![TextEdit {
+                    span: member.span,
+                    replacement: props_name.to_string(),
+                }],
+            });
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn visit_this_expr(&mut self, this: &ThisExpr) {
+        self.diagnostics.push(
+            Diagnostic::warning(
+                RULE,
+                this.span,
+                "`this` does not refer to the component in a Rask component, components are functions and not class instances",
+            )
+            .with_suggestion("Keep values in setup variables or `useState` and read them directly"),
+        );
+    }
+}
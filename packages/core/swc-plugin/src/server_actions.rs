@@ -0,0 +1,500 @@
+use swc_core::common::{Span, Spanned, DUMMY_SP};
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::{private_ident, quote_ident},
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+use crate::diagnostics::Diagnostic;
+use crate::Target;
+
+pub const RULE: &str = "server-action";
+
+const DIRECTIVE: &str = "use server";
+
+/// Name of the generated export mapping action IDs to the server functions, see `createActionHandler`
+pub const REGISTRY_EXPORT: &str = "__raskActions";
+
+/// A module level function marked with "use server", by the module or its own body
+struct ServerAction {
+    /// Index of the module item declaring the action
+    item: usize,
+    /// Index of the declarator, for actions declared with `const`
+    declarator: Option<usize>,
+    local: Option<Ident>,
+    id: String,
+    span: Span,
+    is_async: bool,
+}
+
+/// Compile the "use server" functions of a module. The client gets stubs calling the server
+/// through `callServerAction`, the `ssr` target keeps the functions and exports them by action ID.
+/// A "use server" module is replaced by its stubs on the client, so server code never reaches the bundle.
+/// Other modules lose the imports only their actions used.
+pub fn compile_module(
+    module: &mut Module,
+    file_hash: Option<&str>,
+    target: Target,
+    import_source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let is_server_module = module
+        .body
+        .iter()
+        .map_while(|item| match item {
+            ModuleItem::Stmt(stmt) if string_statement(stmt).is_some() => Some(stmt),
+            _ => None,
+        })
+        .any(|stmt| string_statement(stmt) == Some(DIRECTIVE));
+
+    let actions = collect(module, is_server_module, file_hash, diagnostics);
+
+    let mut checker = ScopeChecker {
+        actions: actions.iter().map(|action| action.span).collect(),
+        diagnostics: &mut *diagnostics,
+    };
+    module.visit_with(&mut checker);
+
+    for action in &actions {
+        if !action.is_async {
+            diagnostics.push(
+                Diagnostic::error(
+                    RULE,
+                    action.span,
+                    "Server actions are called over the network, so they must be async functions",
+                )
+                .with_suggestion("Mark the function `async`"),
+            );
+        }
+    }
+
+    if actions.is_empty() {
+        return;
+    }
+
+    match target {
+        Target::Client => replace_with_stubs(module, &actions, is_server_module, import_source),
+        Target::Ssr => append_registry(module, actions),
+    }
+}
+
+/// The value of an expression statement that is a plain string, as directives are
+fn string_statement(stmt: &Stmt) -> Option<&str> {
+    match stmt {
+        Stmt::Expr(ExprStmt { expr, .. }) => match &**expr {
+            Expr::Lit(Lit::Str(value)) => value.value.as_str(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn has_directive(body: Option<&BlockStmt>) -> bool {
+    body.is_some_and(|body| {
+        body.stmts
+            .iter()
+            .map_while(string_statement)
+            .any(|directive| directive == DIRECTIVE)
+    })
+}
+
+/// The body, async-ness and span of a function value, `None` for other expressions.
+/// The span is the function's own, without the parens around it, as [`ScopeChecker`] visits it
+fn function_value(expr: &Expr) -> Option<(Option<&BlockStmt>, bool, Span)> {
+    match expr {
+        Expr::Fn(fn_expr) => Some((
+            fn_expr.function.body.as_ref(),
+            fn_expr.function.is_async,
+            fn_expr.function.span,
+        )),
+        Expr::Arrow(arrow) => match &*arrow.body {
+            BlockStmtOrExpr::BlockStmt(body) => Some((Some(body), arrow.is_async, arrow.span)),
+            _ => Some((None, arrow.is_async, arrow.span)),
+        },
+        Expr::Paren(paren) => function_value(&paren.expr),
+        _ => None,
+    }
+}
+
+fn collect(
+    module: &Module,
+    is_server_module: bool,
+    file_hash: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<ServerAction> {
+    let action_id = |name: &str| match file_hash {
+        Some(hash) => format!("{}:{}", hash, name),
+        None => name.to_string(),
+    };
+    let not_a_function = |span: Span| {
+        Diagnostic::error(
+            RULE,
+            span,
+            "A \"use server\" module can only export async functions, its other code stays on the server",
+        )
+    };
+
+    let mut actions = vec![];
+
+    for (item_index, item) in module.body.iter().enumerate() {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => {
+                if has_directive(fn_decl.function.body.as_ref()) {
+                    actions.push(ServerAction {
+                        item: item_index,
+                        declarator: None,
+                        local: Some(fn_decl.ident.clone()),
+                        id: action_id(&fn_decl.ident.sym),
+                        span: fn_decl.function.span,
+                        is_async: fn_decl.function.is_async,
+                    });
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Fn(fn_decl),
+                ..
+            })) => {
+                if is_server_module || has_directive(fn_decl.function.body.as_ref()) {
+                    actions.push(ServerAction {
+                        item: item_index,
+                        declarator: None,
+                        local: Some(fn_decl.ident.clone()),
+                        id: action_id(&fn_decl.ident.sym),
+                        span: fn_decl.function.span,
+                        is_async: fn_decl.function.is_async,
+                    });
+                }
+            }
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var)))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var), ..
+            })) => {
+                let is_exported = matches!(item, ModuleItem::ModuleDecl(_));
+                for (declarator_index, declarator) in var.decls.iter().enumerate() {
+                    let function = declarator.init.as_deref().and_then(function_value);
+                    let Some((body, is_async, span)) = function else {
+                        if is_server_module && is_exported {
+                            diagnostics.push(not_a_function(declarator.span));
+                        }
+                        continue;
+                    };
+                    if !(is_server_module && is_exported) && !has_directive(body) {
+                        continue;
+                    }
+                    let Pat::Ident(name) = &declarator.name else {
+                        continue;
+                    };
+                    actions.push(ServerAction {
+                        item: item_index,
+                        declarator: Some(declarator_index),
+                        local: Some(name.id.clone()),
+                        id: action_id(&name.id.sym),
+                        span,
+                        is_async,
+                    });
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                decl: DefaultDecl::Fn(fn_expr),
+                ..
+            })) => {
+                if is_server_module || has_directive(fn_expr.function.body.as_ref()) {
+                    actions.push(ServerAction {
+                        item: item_index,
+                        declarator: None,
+                        local: fn_expr.ident.clone(),
+                        id: action_id("default"),
+                        span: fn_expr.function.span,
+                        is_async: fn_expr.function.is_async,
+                    });
+                }
+            }
+            // Type exports are erased, so they can stay
+            ModuleItem::ModuleDecl(
+                ModuleDecl::ExportDefaultExpr(_)
+                | ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                    decl: DefaultDecl::Class(_),
+                    ..
+                })
+                | ModuleDecl::ExportNamed(NamedExport { type_only: false, .. })
+                | ModuleDecl::ExportAll(ExportAll { type_only: false, .. })
+                | ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Class(_), ..
+                }),
+            ) if is_server_module => {
+                diagnostics.push(not_a_function(item.span()));
+            }
+            _ => {}
+        }
+    }
+
+    actions
+}
+
+/// Reports "use server" functions that are not declared at module level, they could close over client state
+struct ScopeChecker<'a> {
+    actions: Vec<Span>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl ScopeChecker<'_> {
+    fn check(&mut self, span: Span, body: Option<&BlockStmt>) {
+        if has_directive(body) && !self.actions.contains(&span) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    RULE,
+                    span,
+                    "\"use server\" functions must be declared at module level, a nested function could depend on values that only exist on the client",
+                )
+                .with_suggestion("Move the function to module level and pass the values it needs as arguments"),
+            );
+        }
+    }
+}
+
+impl Visit for ScopeChecker<'_> {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, func: &Function) {
+        self.check(func.span, func.body.as_ref());
+        func.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        if let BlockStmtOrExpr::BlockStmt(body) = &*arrow.body {
+            self.check(arrow.span, Some(body));
+        }
+        arrow.visit_children_with(self);
+    }
+}
+
+/// Replace the actions with `(...args) => callServerAction("<id>", args)` stubs
+fn replace_with_stubs(module: &mut Module, actions: &[ServerAction], is_server_module: bool, import_source: &str) {
+    let call_server_action = private_ident!("callServerAction");
+    let used_before = used_ids(module);
+
+    for action in actions {
+        let args = private_ident!("args");
+        let call = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(Expr::Ident(call_server_action.clone()))),
+            args: vec![
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(string(&action.id)))),
+                },
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Ident(args.clone())),
+                },
+            ],
+            type_args: None,
+        });
+        let rest = Pat::Rest(RestPat {
+            span: DUMMY_SP,
+            dot3_token: DUMMY_SP,
+            arg: Box::new(Pat::Ident(args.into())),
+            type_ann: None,
+        });
+
+        let function = match (&mut module.body[action.item], action.declarator) {
+            (ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))), None)
+            | (
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Fn(fn_decl),
+                    ..
+                })),
+                None,
+            ) => &mut *fn_decl.function,
+            (
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                    decl: DefaultDecl::Fn(fn_expr),
+                    ..
+                })),
+                None,
+            ) => &mut *fn_expr.function,
+            (ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))), Some(index))
+            | (
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(var), ..
+                })),
+                Some(index),
+            ) => {
+                let init = &mut var.decls[index].init;
+                let span = init.as_ref().map(|init| init.span()).unwrap_or(DUMMY_SP);
+                *init = Some(Box::new(Expr::Arrow(ArrowExpr {
+                    span,
+                    ctxt: Default::default(),
+                    params: vec![rest],
+                    body: Box::new(BlockStmtOrExpr::Expr(Box::new(call))),
+                    is_async: false,
+                    is_generator: false,
+                    type_params: None,
+                    return_type: None,
+                })));
+                continue;
+            }
+            _ => continue,
+        };
+
+        function.params = vec![Param {
+            span: DUMMY_SP,
+            decorators: vec![],
+            pat: rest,
+        }];
+        function.body = Some(BlockStmt {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            stmts: vec![Stmt::Return(ReturnStmt {
+                span: DUMMY_SP,
+                arg: Some(Box::new(call)),
+            })],
+        });
+        function.is_async = false;
+        function.is_generator = false;
+        function.type_params = None;
+        function.return_type = None;
+    }
+
+    // Only the exported stubs of a "use server" module are kept
+    if is_server_module {
+        let body = std::mem::take(&mut module.body);
+        module.body = body
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, mut item)| {
+                let item_actions: Vec<&ServerAction> = actions.iter().filter(|action| action.item == index).collect();
+                if item_actions.is_empty() || !matches!(item, ModuleItem::ModuleDecl(_)) {
+                    return None;
+                }
+                if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(var), ..
+                })) = &mut item
+                {
+                    let decls = std::mem::take(&mut var.decls);
+                    var.decls = decls
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(index, _)| item_actions.iter().any(|action| action.declarator == Some(*index)))
+                        .map(|(_, declarator)| declarator)
+                        .collect();
+                }
+                Some(item)
+            })
+            .collect();
+    } else {
+        remove_unused_imports(module, &used_before);
+    }
+
+    module.body.insert(
+        0,
+        ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: call_server_action,
+                imported: Some(ModuleExportName::Ident(quote_ident!("callServerAction").into())),
+                is_type_only: false,
+            })],
+            src: Box::new(string(import_source)),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        })),
+    );
+}
+
+/// Remove the import bindings only the stubbed actions used, so their server modules are not bundled for the client
+fn remove_unused_imports(module: &mut Module, used_before: &[Id]) {
+    let used = used_ids(module);
+    let is_unused = |local: &Ident| used_before.contains(&local.to_id()) && !used.contains(&local.to_id());
+
+    module.body.retain_mut(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return true;
+        };
+        if import.specifiers.is_empty() {
+            return true;
+        }
+        import.specifiers.retain(|specifier| match specifier {
+            ImportSpecifier::Named(named) => !is_unused(&named.local),
+            ImportSpecifier::Default(default) => !is_unused(&default.local),
+            ImportSpecifier::Namespace(namespace) => !is_unused(&namespace.local),
+        });
+        !import.specifiers.is_empty()
+    });
+}
+
+/// The bindings referenced outside of imports
+fn used_ids(module: &Module) -> Vec<Id> {
+    let mut collector = UsedIds(vec![]);
+    module.visit_with(&mut collector);
+    collector.0
+}
+
+struct UsedIds(Vec<Id>);
+
+impl Visit for UsedIds {
+    noop_visit_type!();
+
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.0.push(ident.to_id());
+    }
+}
+
+/// Export the actions by ID, `export const __raskActions = { "<id>": action }`, for `createActionHandler`
+fn append_registry(module: &mut Module, actions: Vec<ServerAction>) {
+    let mut props = vec![];
+
+    for action in actions {
+        let local = match action.local {
+            Some(local) => local,
+            // An anonymous default export needs a binding to be registered
+            None => {
+                let local = private_ident!("serverAction");
+                if let ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                    decl: DefaultDecl::Fn(fn_expr),
+                    ..
+                })) = &mut module.body[action.item]
+                {
+                    fn_expr.ident = Some(local.clone());
+                }
+                local
+            }
+        };
+        props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Str(string(&action.id)),
+            value: Box::new(Expr::Ident(local)),
+        }))));
+    }
+
+    module
+        .body
+        .push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Var(Box::new(VarDecl {
+                span: DUMMY_SP,
+                ctxt: Default::default(),
+                kind: VarDeclKind::Const,
+                declare: false,
+                decls: vec![VarDeclarator {
+                    span: DUMMY_SP,
+                    name: Pat::Ident(Ident::from(quote_ident!(REGISTRY_EXPORT)).into()),
+                    init: Some(Box::new(Expr::Object(ObjectLit { span: DUMMY_SP, props }))),
+                    definite: false,
+                }],
+            })),
+        })));
+}
+
+fn string(value: &str) -> Str {
+    Str {
+        span: DUMMY_SP,
+        value: Wtf8Atom::from(value),
+        raw: None,
+    }
+}
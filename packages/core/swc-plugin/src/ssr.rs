@@ -0,0 +1,506 @@
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::{
+    ast::*,
+    atoms::Wtf8Atom,
+    utils::{private_ident, quote_ident},
+    visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+};
+
+use crate::islands;
+
+/// The Inferno factories whose calls are compiled to strings
+const VNODE_FACTORIES: &[&str] = &[
+    "createVNode",
+    "createComponentVNode",
+    "createFragment",
+    "createTextVNode",
+    "normalizeProps",
+];
+
+/// Elements without children or a closing tag, matches `VOID_ELEMENTS` in the runtime
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Attributes rendered by presence, matches `BOOLEAN_ATTRIBUTES` in the runtime
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// A piece of rendered markup, known at compile time or computed by a runtime helper
+enum Part {
+    Static(String),
+    Dynamic(Expr),
+}
+
+/// Compiles the VNode factory calls of Inferno's JSX output into HTML string building.
+/// Calls nested in an element are inlined into its string, other calls become `ssrHtml(...)`
+/// so the runtime can tell rendered markup from text that still needs escaping.
+pub struct SsrRender {
+    helpers: Vec<(&'static str, Ident)>,
+    /// Bracket dynamic children with `<!--[-->`/`<!--]-->` and mark component boundaries
+    hydrate: bool,
+}
+
+/// Compile the render output of a module for the `ssr` target and import the runtime helpers it uses
+pub fn compile_module(module: &mut Module, import_source: &str, hydrate: bool) {
+    let mut render = SsrRender { helpers: vec![], hydrate };
+    module.visit_mut_with(&mut render);
+
+    strip_factory_imports(module);
+
+    if render.helpers.is_empty() {
+        return;
+    }
+    let specifiers = render
+        .helpers
+        .into_iter()
+        .map(|(name, local)| {
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local,
+                imported: Some(ModuleExportName::Ident(quote_ident!(name).into())),
+                is_type_only: false,
+            })
+        })
+        .collect();
+    module.body.insert(
+        0,
+        ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: DUMMY_SP,
+            specifiers,
+            src: Box::new(Str {
+                span: DUMMY_SP,
+                value: Wtf8Atom::from(format!("{}/ssr", import_source).as_str()),
+                raw: None,
+            }),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        })),
+    );
+}
+
+/// Remove the factory imports from "inferno" that the compiled output no longer uses
+fn strip_factory_imports(module: &mut Module) {
+    module.body.retain_mut(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return true;
+        };
+        if &*import.src.value != "inferno" || import.specifiers.is_empty() {
+            return true;
+        }
+        import.specifiers.retain(|specifier| match specifier {
+            ImportSpecifier::Named(named) => !VNODE_FACTORIES.iter().any(|name| *name == &*named.local.sym),
+            _ => true,
+        });
+        !import.specifiers.is_empty()
+    });
+}
+
+pub(crate) fn factory_name(call: &CallExpr) -> Option<&'static str> {
+    let Callee::Expr(callee) = &call.callee else {
+        return None;
+    };
+    let Expr::Ident(ident) = &**callee else {
+        return None;
+    };
+    VNODE_FACTORIES.iter().copied().find(|name| *name == &*ident.sym)
+}
+
+/// An argument of a factory call, `None` when it is left out or passed as `null`/`undefined`
+pub(crate) fn arg(call: &CallExpr, index: usize) -> Option<&Expr> {
+    let arg = call.args.get(index)?;
+    if arg.spread.is_some() || is_empty(&arg.expr) {
+        return None;
+    }
+    Some(&arg.expr)
+}
+
+pub(crate) fn is_empty(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Null(_)) => true,
+        Expr::Ident(ident) => &*ident.sym == "undefined",
+        Expr::Unary(UnaryExpr { op: UnaryOp::Void, .. }) => true,
+        _ => false,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+fn string(value: &str) -> Expr {
+    Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: Wtf8Atom::from(value),
+        raw: None,
+    }))
+}
+
+pub(crate) fn str_value(str: &Str) -> String {
+    str.value.to_string_lossy().into_owned()
+}
+
+/// Whether an attribute ends up in the markup, matches `isRenderedAttribute` in the runtime
+fn is_rendered_attribute(name: &str) -> bool {
+    let is_event = name.len() > 2 && name.starts_with("on") && name[2..].starts_with(|c: char| c.is_ascii_uppercase());
+    !matches!(name, "key" | "ref" | "children" | "dangerouslySetInnerHTML") && !name.starts_with('$') && !is_event
+}
+
+/// The value of an element's `dangerouslySetInnerHTML` prop, whose markup replaces the children
+pub(crate) fn inner_html(props: Option<&Expr>) -> Option<&Expr> {
+    let Some(Expr::Object(props)) = props else {
+        return None;
+    };
+    props.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp { key, value }) => {
+                let name = match key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(str) => str_value(str),
+                    _ => return None,
+                };
+                (name == "dangerouslySetInnerHTML").then_some(&**value)
+            }
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    })
+}
+
+fn attribute_name(name: &str) -> &str {
+    match name {
+        "className" => "class",
+        "htmlFor" => "for",
+        _ => name,
+    }
+}
+
+/// The markup of an attribute with a literal value, `None` when it has to be rendered at runtime
+fn static_attr(name: &str, value: &Expr) -> Option<String> {
+    let name = attribute_name(name);
+    let Expr::Lit(lit) = value else {
+        return is_empty(value).then(String::new);
+    };
+    let truthy = match lit {
+        Lit::Str(str) => !str.value.is_empty(),
+        Lit::Bool(bool) => bool.value,
+        Lit::Num(num) => num.value != 0.0 && !num.value.is_nan(),
+        Lit::Null(_) => false,
+        _ => return None,
+    };
+
+    if BOOLEAN_ATTRIBUTES.iter().any(|attribute| attribute.eq_ignore_ascii_case(name)) {
+        return Some(if truthy { format!(" {}", name) } else { String::new() });
+    }
+    let value = match lit {
+        Lit::Str(str) => str_value(str),
+        // Style objects and computed values are rendered by `ssrAttr`
+        _ if name == "style" => return None,
+        Lit::Num(num) => num.value.to_string(),
+        Lit::Bool(bool) if bool.value => "true".to_string(),
+        Lit::Bool(_) if name.starts_with("aria-") || name.starts_with("data-") => "false".to_string(),
+        _ => return Some(String::new()),
+    };
+    if name == "style" && value.is_empty() {
+        return Some(String::new());
+    }
+    Some(format!(" {}=\"{}\"", name, escape_html(&value)))
+}
+
+impl SsrRender {
+    /// The local name of a runtime helper, imported once per module
+    fn helper(&mut self, name: &'static str) -> Ident {
+        if let Some((_, local)) = self.helpers.iter().find(|(helper, _)| *helper == name) {
+            return local.clone();
+        }
+        let local = private_ident!(name);
+        self.helpers.push((name, local.clone()));
+        local
+    }
+
+    fn call(&mut self, helper: &'static str, args: Vec<Expr>) -> Expr {
+        Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(Expr::Ident(self.helper(helper)))),
+            args: args
+                .into_iter()
+                .map(|expr| ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(expr),
+                })
+                .collect(),
+            type_args: None,
+        })
+    }
+
+    /// A dynamic expression with its own factory calls compiled
+    fn visited(&mut self, expr: &Expr) -> Expr {
+        let mut expr = expr.clone();
+        expr.visit_mut_with(self);
+        expr
+    }
+
+    fn compile(&mut self, call: &CallExpr, parts: &mut Vec<Part>) {
+        match factory_name(call) {
+            Some("createVNode") => self.element(call, parts),
+            Some("createComponentVNode") => {
+                let component = arg(call, 1).cloned().unwrap_or_else(|| Expr::Ident(quote_ident!("undefined").into()));
+                if let (Expr::Ident(name), Some(Expr::Object(props))) = (&component, arg(call, 2)) {
+                    if let Some((strategy, index)) = islands::directive(props) {
+                        let mut props = props.clone();
+                        props.props.remove(index);
+                        let props = self.visited(&Expr::Object(props));
+                        let island = self.call(
+                            "ssrIsland",
+                            vec![component.clone(), props, string(strategy), string(&name.sym)],
+                        );
+                        self.region(island, parts);
+                        return;
+                    }
+                }
+
+                let mut args = vec![component];
+                if let Some(props) = arg(call, 2) {
+                    args.push(self.visited(props));
+                }
+                if self.hydrate {
+                    if args.len() == 1 {
+                        args.push(Expr::Ident(quote_ident!("undefined").into()));
+                    }
+                    args.push(Expr::Lit(Lit::Bool(Bool {
+                        span: DUMMY_SP,
+                        value: true,
+                    })));
+                }
+                let component = self.call("ssrComponent", args);
+                self.region(component, parts);
+            }
+            Some("createFragment") | Some("createTextVNode") => {
+                if let Some(children) = arg(call, 0) {
+                    self.child(children, parts);
+                }
+            }
+            // Spread props are merged at runtime, the rendered element is the same
+            Some("normalizeProps") => {
+                if let Some(vnode) = arg(call, 0) {
+                    self.child(vnode, parts);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn element(&mut self, call: &CallExpr, parts: &mut Vec<Part>) {
+        let Some(Expr::Lit(Lit::Str(tag))) = arg(call, 1) else {
+            // Dynamic tags are only known at runtime
+            let mut args = vec![];
+            for index in [1, 2, 5, 3] {
+                args.push(match arg(call, index) {
+                    Some(expr) => self.visited(expr),
+                    None => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+                });
+            }
+            let element = self.call("ssrElement", args);
+            self.region(element, parts);
+            return;
+        };
+        let tag = str_value(tag);
+
+        parts.push(Part::Static(format!("<{}", tag)));
+        if let Some(class_name) = arg(call, 2) {
+            self.attr("className", class_name, parts);
+        }
+        match arg(call, 5) {
+            Some(Expr::Object(props)) => {
+                for prop in &props.props {
+                    self.prop(prop, parts);
+                }
+            }
+            Some(props) => {
+                let props = self.visited(props);
+                parts.push(Part::Dynamic(self.call("ssrAttrs", vec![props])));
+            }
+            None => {}
+        }
+        parts.push(Part::Static(">".to_string()));
+
+        if VOID_ELEMENTS.iter().any(|element| *element == tag) {
+            return;
+        }
+        if let Some(html) = inner_html(arg(call, 5)) {
+            let html = self.visited(html);
+            let html = self.call("ssrInnerHtml", vec![html]);
+            self.region(html, parts);
+        } else if let Some(children) = arg(call, 3) {
+            self.child(children, parts);
+        }
+        parts.push(Part::Static(format!("</{}>", tag)));
+    }
+
+    fn prop(&mut self, prop: &PropOrSpread, parts: &mut Vec<Part>) {
+        let prop = match prop {
+            PropOrSpread::Spread(spread) => {
+                let props = self.visited(&spread.expr);
+                parts.push(Part::Dynamic(self.call("ssrAttrs", vec![props])));
+                return;
+            }
+            PropOrSpread::Prop(prop) => prop,
+        };
+        match &**prop {
+            Prop::KeyValue(KeyValueProp { key, value }) => match key {
+                PropName::Ident(ident) => self.attr(&ident.sym, value, parts),
+                PropName::Str(str) => self.attr(&str_value(str), value, parts),
+                _ => {
+                    let props = Expr::Object(ObjectLit {
+                        span: DUMMY_SP,
+                        props: vec![PropOrSpread::Prop(prop.clone())],
+                    });
+                    let props = self.visited(&props);
+                    parts.push(Part::Dynamic(self.call("ssrAttrs", vec![props])));
+                }
+            },
+            Prop::Shorthand(ident) => self.attr(&ident.sym, &Expr::Ident(ident.clone()), parts),
+            // Methods and accessors are never rendered as attributes
+            _ => {}
+        }
+    }
+
+    fn attr(&mut self, name: &str, value: &Expr, parts: &mut Vec<Part>) {
+        if !is_rendered_attribute(name) {
+            return;
+        }
+        if let Some(markup) = static_attr(name, value) {
+            parts.push(Part::Static(markup));
+            return;
+        }
+        let value = self.visited(value);
+        parts.push(Part::Dynamic(self.call("ssrAttr", vec![string(name), value])));
+    }
+
+    fn child(&mut self, expr: &Expr, parts: &mut Vec<Part>) {
+        match expr {
+            Expr::Paren(paren) => self.child(&paren.expr, parts),
+            Expr::Call(call) if factory_name(call).is_some() => self.compile(call, parts),
+            Expr::Lit(Lit::Str(str)) => parts.push(Part::Static(escape_html(&str_value(str)))),
+            Expr::Lit(Lit::Num(num)) => parts.push(Part::Static(num.value.to_string())),
+            Expr::Lit(Lit::Bool(_)) => {}
+            Expr::Array(array) if array.elems.iter().flatten().all(|elem| elem.spread.is_none()) => {
+                for elem in array.elems.iter().flatten() {
+                    self.child(&elem.expr, parts);
+                }
+            }
+            _ if is_empty(expr) => {}
+            _ => {
+                let child = self.visited(expr);
+                let child = self.call("ssrChild", vec![child]);
+                self.region(child, parts);
+            }
+        }
+    }
+
+    /// Markup only known at runtime, which the client structure check sees as a dynamic region
+    fn region(&mut self, expr: Expr, parts: &mut Vec<Part>) {
+        if !self.hydrate {
+            parts.push(Part::Dynamic(expr));
+            return;
+        }
+        parts.push(Part::Static("<!--[-->".to_string()));
+        parts.push(Part::Dynamic(expr));
+        parts.push(Part::Static("<!--]-->".to_string()));
+    }
+
+    /// Concatenate the parts, merging adjacent static markup into one string literal
+    fn concat(parts: Vec<Part>) -> Expr {
+        let mut exprs: Vec<Expr> = vec![];
+        let mut markup = String::new();
+        let string = |markup: &str| {
+            Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: Wtf8Atom::from(markup),
+                raw: None,
+            }))
+        };
+
+        for part in parts {
+            match part {
+                Part::Static(text) => markup.push_str(&text),
+                Part::Dynamic(expr) => {
+                    if !markup.is_empty() {
+                        exprs.push(string(&markup));
+                        markup.clear();
+                    }
+                    exprs.push(expr);
+                }
+            }
+        }
+        // An element without any parts still renders an empty string
+        if !markup.is_empty() || exprs.is_empty() {
+            exprs.push(string(&markup));
+        }
+
+        exprs
+            .into_iter()
+            .reduce(|left, right| {
+                Expr::Bin(BinExpr {
+                    span: DUMMY_SP,
+                    op: BinaryOp::Add,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                })
+            })
+            .expect("concatenation has at least one operand")
+    }
+}
+
+impl VisitMut for SsrRender {
+    noop_visit_mut_type!();
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Call(call) = expr {
+            if factory_name(call).is_some() {
+                let mut parts = vec![];
+                self.compile(call, &mut parts);
+                let html = Self::concat(parts);
+                *expr = self.call("ssrHtml", vec![html]);
+                return;
+            }
+        }
+        expr.visit_mut_children_with(self);
+    }
+}
@@ -0,0 +1,286 @@
+#![cfg(feature = "native")]
+
+use std::{fs, path::PathBuf};
+
+use swc_core::common::{sync::Lrc, SourceMap};
+use swc_plugin_rask_component::codemod::{
+    convert::{self, Direction},
+    migrations, react, CodemodResult,
+};
+
+/// Write a fixture to its own file, codemods read the files they rewrite
+fn fixture(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rask-codemod-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn convert(name: &str, source: &str, direction: Direction) -> CodemodResult {
+    let cm: Lrc<SourceMap> = Default::default();
+    convert::run(&cm, &fixture(name, source), direction, None).unwrap()
+}
+
+fn migrate_react(name: &str, source: &str) -> CodemodResult {
+    let cm: Lrc<SourceMap> = Default::default();
+    react::run(&cm, &fixture(name, source)).unwrap()
+}
+
+fn migrate_rask(name: &str, source: &str, from: Option<&str>) -> CodemodResult {
+    let cm: Lrc<SourceMap> = Default::default();
+    let pending = migrations::pending(from).unwrap();
+    migrations::run(&cm, &fixture(name, source), &pending, "rask-ui").unwrap()
+}
+
+#[test]
+fn convert_to_stateful_moves_hooks_into_setup_and_indents_render() {
+    let result = convert(
+        "Greeting.tsx",
+        r#"import { useDerived, useState } from "rask-ui";
+
+export function Greeting(props) {
+    const name = props.name.trim();
+    const state = useState({ count: 0 });
+    const title = `Hello
+${name}`;
+    return (
+        <p title={title}>
+            {name} {state.count}
+        </p>
+    );
+}
+
+export function Total(props) {
+    const items = props.items.filter(Boolean);
+    const total = useDerived(() => items.length);
+    return <p>{total.value}</p>;
+}
+"#,
+        Direction::Stateful,
+    );
+
+    assert_eq!(
+        result.output,
+        r#"import { useDerived, useState } from "rask-ui";
+
+export function Greeting(props) {
+    const state = useState({ count: 0 });
+    return () => {
+        const name = props.name.trim();
+        const title = `Hello
+${name}`;
+        return (
+            <p title={title}>
+                {name} {state.count}
+            </p>
+        );
+    };
+}
+
+export function Total(props) {
+    const items = props.items.filter(Boolean);
+    const total = useDerived(() => items.length);
+    return <p>{total.value}</p>;
+}
+"#
+    );
+    assert_eq!(result.applied, ["`Greeting`: Convert to a stateful component"]);
+    assert_eq!(
+        result.skipped,
+        ["`Total` calls a hook that uses `items`, which is computed while rendering"]
+    );
+}
+
+#[test]
+fn convert_to_stateless_inlines_render_and_reports_hooks_in_setup() {
+    let result = convert(
+        "Badge.tsx",
+        r#"import { useState } from "rask-ui";
+
+export function Badge(props) {
+    const label = props.label.toUpperCase();
+    return () => {
+        const title = `${label}
+!`;
+        return <span title={title}>{label}</span>;
+    };
+}
+
+export function Clock() {
+    const state = useState({ now: Date.now() });
+    return () => <time>{state.now}</time>;
+}
+"#,
+        Direction::Stateless,
+    );
+
+    assert_eq!(
+        result.output,
+        r#"import { useState } from "rask-ui";
+
+export function Badge(props) {
+    const label = props.label.toUpperCase();
+    const title = `${label}
+!`;
+    return <span title={title}>{label}</span>;
+}
+
+export function Clock() {
+    const state = useState({ now: Date.now() });
+    return () => <time>{state.now}</time>;
+}
+"#
+    );
+    assert_eq!(result.applied, ["`Badge`: Convert to a stateless component"]);
+    assert_eq!(
+        result.skipped,
+        ["`Clock` calls `useState` in setup, hooks can not run on every render"]
+    );
+}
+
+#[test]
+fn react_hooks_merge_into_state_derived_and_effects() {
+    let result = migrate_react(
+        "Counter.tsx",
+        r#"import { useEffect, useMemo, useState } from "react";
+
+export function Counter(props) {
+    const [count, setCount] = useState(0);
+    const [label, setLabel] = useState(() => props.label);
+    const double = useMemo(() => count * 2, [count]);
+    useEffect(() => {
+        const id = setInterval(() => setCount((value) => value + 1), 1000);
+        return () => clearInterval(id);
+    }, []);
+    return <Input value={label} double={double} onChange={setLabel} />;
+}
+"#,
+    );
+
+    assert_eq!(
+        result.output,
+        r#"import { useDerived, useEffect, useState } from "rask-ui";
+
+export function Counter(props) {
+    const state = useState({ count: 0, label: (() => props.label)() });
+    const derived = useDerived({ double: () => state.count * 2 });
+    useEffect(() => {
+        const id = setInterval(() => state.count = ((value) => value + 1)(state.count), 1000);
+        return () => clearInterval(id);
+    });
+    return () => <Input value={state.label} double={derived.double} onChange={(value) => (state.label = typeof value === "function" ? value(state.label) : value)} />;
+}
+"#
+    );
+    assert_eq!(result.applied, ["`Counter`: Converted to a Rask component"]);
+    assert!(result.skipped.is_empty());
+}
+
+#[test]
+fn react_hooks_using_render_values_or_clashing_names_are_left_to_convert_by_hand() {
+    let result = migrate_react(
+        "Total.tsx",
+        r#"import { useMemo, useState } from "react";
+
+export function Total(props) {
+    const items = props.items.filter(Boolean);
+    const total = useMemo(() => items.length, [items]);
+    return <p>{total}</p>;
+}
+
+export function Panel(props) {
+    const [open, setOpen] = useState(false);
+    const state = props.state;
+    return <div hidden={!open} onClick={() => setOpen(!open)}>{state}</div>;
+}
+"#,
+    );
+
+    assert_eq!(
+        result.output,
+        r#"import { useMemo, useState } from "react";
+
+export function Total(props) {
+    return () => {
+        const items = props.items.filter(Boolean);
+        // TODO(rask): `useMemo` uses `items`, which is computed while rendering, move the hook into setup by hand
+        const total = useMemo(() => items.length, [items]);
+        return <p>{total}</p>;
+    };
+}
+
+export function Panel(props) {
+    const [open, setOpen] = useState(false);
+    const state = props.state;
+    return <div hidden={!open} onClick={() => setOpen(!open)}>{state}</div>;
+}
+"#
+    );
+    assert_eq!(result.applied, ["`Total`: Converted to a Rask component"]);
+    assert_eq!(
+        result.skipped,
+        [
+            "line 5: `useMemo` uses `items`, which is computed while rendering, move the hook into setup by hand",
+            "line 9: `Panel` already declares `state`, which the converted hooks are stored in",
+        ]
+    );
+}
+
+const OLD_API: &str = r#"import { createState, createMutation, useView } from "rask-ui";
+
+export function Counter() {
+    const state = createState({ count: 0 });
+    const save = createMutation(() => fetch("/save"));
+    return () => <p onClick={() => save.mutate()}>{state.count}</p>;
+}
+"#;
+
+#[test]
+fn migrations_are_pending_for_releases_after_the_given_version() {
+    fn versions(from: Option<&str>) -> Vec<&'static str> {
+        let pending = migrations::pending(from).unwrap();
+        pending.iter().map(|migration| migration.version).collect()
+    }
+
+    assert_eq!(versions(None), ["0.29.3"]);
+    assert_eq!(versions(Some("0.28")), ["0.29.3"]);
+    assert_eq!(versions(Some("v0.29.2")), ["0.29.3"]);
+    assert!(versions(Some("0.29.3")).is_empty());
+    assert!(versions(Some("1.0.0")).is_empty());
+    assert_eq!(
+        migrations::pending(Some("latest")).unwrap_err(),
+        "Invalid version latest"
+    );
+}
+
+#[test]
+fn rask_migration_renames_exports_and_reports_removed_ones() {
+    let result = migrate_rask("OldApi.tsx", OLD_API, Some("0.28.0"));
+
+    assert_eq!(
+        result.output,
+        r#"import { useState, createMutation, useView } from "rask-ui";
+
+export function Counter() {
+    const state = useState({ count: 0 });
+    const save = createMutation(() => fetch("/save"));
+    return () => <p onClick={() => save.mutate()}>{state.count}</p>;
+}
+"#
+    );
+    assert_eq!(result.applied, ["`createState` is now `useState`"]);
+    assert_eq!(
+        result.skipped,
+        ["line 1: `createMutation` was removed, use `const [state, run] = useAction(fn)`, read `state.isPending`/`state.error` and call `run(params)` instead of `mutate`"]
+    );
+}
+
+#[test]
+fn rask_migration_skips_releases_up_to_the_given_version() {
+    let result = migrate_rask("CurrentApi.tsx", OLD_API, Some("0.29.3"));
+
+    assert!(!result.changed());
+    assert!(result.applied.is_empty());
+    assert!(result.skipped.is_empty());
+}
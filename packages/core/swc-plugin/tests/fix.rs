@@ -0,0 +1,109 @@
+use swc_core::common::{BytePos, Span};
+use swc_plugin_rask_component::{
+    diagnostics::{Fix, TextEdit},
+    fix::apply_edits,
+};
+
+/// Source positions start at 1, as they do for the first file of a source map
+const START: BytePos = BytePos(1);
+
+/// Replace the source bytes `lo..hi` with `replacement`
+fn edit(lo: u32, hi: u32, replacement: &str) -> TextEdit {
+    TextEdit {
+        span: Span::new(BytePos(START.0 + lo), BytePos(START.0 + hi)),
+        replacement: replacement.to_string(),
+    }
+}
+
+fn fix(edits: Vec<TextEdit>) -> Fix {
+    Fix {
+        description: "fix".to_string(),
+        edits,
+    }
+}
+
+#[test]
+fn applies_every_edit_of_a_fix() {
+    let source = "const { title } = props;\nlog(title);\nrender(title);\n";
+    let fixes = [fix(vec![
+        edit(6, 15, "props"),
+        edit(29, 34, "props.title"),
+        edit(44, 49, "props.title"),
+    ])];
+
+    let (fixed, accepted) = apply_edits(source, START, &[Some(&fixes[0])]);
+
+    assert_eq!(fixed, "const props = props;\nlog(props.title);\nrender(props.title);\n");
+    assert_eq!(accepted, [0]);
+}
+
+#[test]
+fn applies_edits_given_out_of_order() {
+    let source = "a b c";
+    let fixes = [
+        fix(vec![edit(4, 5, "C")]),
+        fix(vec![edit(0, 1, "A")]),
+        fix(vec![edit(2, 3, "B")]),
+    ];
+
+    let (fixed, accepted) = apply_edits(source, START, &[Some(&fixes[0]), Some(&fixes[1]), Some(&fixes[2])]);
+
+    assert_eq!(fixed, "A B C");
+    assert_eq!(accepted, [0, 1, 2]);
+}
+
+#[test]
+fn skips_fixes_overlapping_an_accepted_fix() {
+    let source = "state.count++";
+    let fixes = [
+        fix(vec![edit(0, 11, "props.count")]),
+        // Overlaps the first fix, including an insertion inside its range
+        fix(vec![edit(6, 13, "total")]),
+        fix(vec![edit(3, 3, "x")]),
+    ];
+
+    let (fixed, accepted) = apply_edits(source, START, &[Some(&fixes[0]), Some(&fixes[1]), Some(&fixes[2])]);
+
+    assert_eq!(fixed, "props.count++");
+    assert_eq!(accepted, [0]);
+}
+
+#[test]
+fn skips_a_whole_fix_when_one_of_its_edits_overlaps() {
+    let source = "one two three";
+    let fixes = [fix(vec![edit(4, 7, "2")]), fix(vec![edit(0, 3, "1"), edit(5, 6, "W")])];
+
+    let (fixed, accepted) = apply_edits(source, START, &[Some(&fixes[0]), Some(&fixes[1])]);
+
+    assert_eq!(fixed, "one 2 three");
+    assert_eq!(accepted, [0]);
+}
+
+#[test]
+fn applies_adjacent_edits_and_keeps_insertions_in_order() {
+    let source = "render()";
+    let fixes = [
+        fix(vec![edit(0, 0, "import { a } from \"a\";\n")]),
+        fix(vec![edit(0, 0, "import { b } from \"b\";\n")]),
+        // Starts where the insertions are, without overlapping them
+        fix(vec![edit(0, 6, "mount")]),
+        fix(vec![edit(6, 8, "(root)")]),
+    ];
+    let fixes: Vec<Option<&Fix>> = fixes.iter().map(Some).collect();
+
+    let (fixed, accepted) = apply_edits(source, START, &fixes);
+
+    assert_eq!(fixed, "import { a } from \"a\";\nimport { b } from \"b\";\nmount(root)");
+    assert_eq!(accepted, [0, 1, 2, 3]);
+}
+
+#[test]
+fn skips_missing_fixes_and_edits_outside_of_the_source() {
+    let source = "value";
+    let fixes = [fix(vec![edit(0, 20, "other")]), fix(vec![edit(0, 5, "other")])];
+
+    let (fixed, accepted) = apply_edits(source, START, &[None, Some(&fixes[0]), Some(&fixes[1])]);
+
+    assert_eq!(fixed, "other");
+    assert_eq!(accepted, [2]);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use swc_core::common::{sync::Lrc, SourceMap};
+use swc_plugin_rask_component::{
+    lint::{lint_source, LintedFile},
+    report, Config,
+};
+
+/// A missing `useState` import, called after a non-ASCII string so columns and byte offsets differ
+const SOURCE: &str = "function Counter() {
+    const label = \"ü\", state = useState({ count: 0 });
+    return () => <p>{label} {state.count}</p>;
+}
+";
+
+fn linted(cm: &Lrc<SourceMap>) -> LintedFile {
+    lint_source(cm, Path::new("src/Counter.tsx"), SOURCE.to_string(), &Config::default())
+}
+
+#[test]
+fn json_report_has_one_based_positions_and_a_summary() {
+    let cm: Lrc<SourceMap> = Default::default();
+    let report: Value = serde_json::from_str(&report::to_json(&cm, &[linted(&cm)])).unwrap();
+
+    assert_eq!(
+        report,
+        json!({
+            "version": 1,
+            "files": [{
+                "path": "src/Counter.tsx",
+                "diagnostics": [{
+                    "rule": "missing-import",
+                    "severity": "warning",
+                    "message": "`useState` is called but not imported from \"rask-ui\"",
+                    "span": {
+                        "start": { "line": 2, "column": 32, "offset": 53 },
+                        "end": { "line": 2, "column": 54, "offset": 75 },
+                    },
+                    "suggestion": "Add `import { useState } from \"rask-ui\"`",
+                    "fix": {
+                        "description": "Import useState from \"rask-ui\"",
+                        "edits": [{
+                            "span": {
+                                "start": { "line": 1, "column": 1, "offset": 0 },
+                                "end": { "line": 1, "column": 1, "offset": 0 },
+                            },
+                            "replacement": "import { useState } from \"rask-ui\";\n",
+                        }],
+                    },
+                }],
+            }],
+            "summary": { "files": 1, "errors": 0, "warnings": 1 },
+        })
+    );
+}
+
+#[test]
+fn sarif_log_has_code_point_columns_and_rule_metadata() {
+    let cm: Lrc<SourceMap> = Default::default();
+    let log: Value = serde_json::from_str(&report::to_sarif(&cm, &[linted(&cm)])).unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+
+    let driver = &run["tool"]["driver"];
+    assert_eq!(driver["name"], "rask-lint");
+    let rules = driver["rules"].as_array().unwrap();
+    assert!(rules.contains(&json!({
+        "id": "missing-import",
+        "shortDescription": { "text": "rask-ui functions must be imported before they are called" },
+    })));
+    assert!(rules.iter().any(|rule| rule["id"] == "parse-error"));
+
+    assert_eq!(
+        run["results"],
+        json!([{
+            "ruleId": "missing-import",
+            "level": "warning",
+            "message": { "text": "`useState` is called but not imported from \"rask-ui\"" },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "src/Counter.tsx" },
+                    "region": { "startLine": 2, "startColumn": 32, "endLine": 2, "endColumn": 54 },
+                },
+            }],
+            "properties": { "suggestion": "Add `import { useState } from \"rask-ui\"`" },
+            "fixes": [{
+                "description": { "text": "Import useState from \"rask-ui\"" },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": "src/Counter.tsx" },
+                    "replacements": [{
+                        "deletedRegion": { "startLine": 1, "startColumn": 1, "endLine": 1, "endColumn": 1 },
+                        "insertedContent": { "text": "import { useState } from \"rask-ui\";\n" },
+                    }],
+                }],
+            }],
+        }])
+    );
+}
@@ -0,0 +1,351 @@
+#![cfg(feature = "native")]
+
+use std::path::Path;
+
+use swc_core::common::{sync::Lrc, SourceMap};
+use swc_plugin_rask_component::{lint::lint_source, Config};
+
+/// The source text covered by each diagnostic of `rule` reported for a component file
+fn reported(source: &str, rule: &str) -> Vec<String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let file = lint_source(&cm, Path::new("Component.tsx"), source.to_string(), &Config::default());
+    file.diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.rule == rule)
+        .map(|diagnostic| cm.span_to_snippet(diagnostic.span).unwrap())
+        .collect()
+}
+
+#[test]
+fn stale_setup_read_reports_reads_in_setup() {
+    let source = r#"
+        import { useState } from "rask-ui";
+
+        function Item(props) {
+            const state = useState({ items: [] });
+            const title = props.title;
+            const item = state.items[props.index];
+            return () => <p>{title} {item}</p>;
+        }
+    "#;
+
+    assert_eq!(
+        reported(source, "stale-setup-read"),
+        ["props.title", "state.items", "props.index"]
+    );
+}
+
+#[test]
+fn stale_setup_read_reports_destructured_props() {
+    let source = r#"
+        function Title({ title }) {
+            return () => <h1>{title}</h1>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "stale-setup-read"), ["{ title }"]);
+}
+
+#[test]
+fn stale_setup_read_allows_reads_in_render_and_seeding_state() {
+    let source = r#"
+        import { useState } from "rask-ui";
+
+        function Counter(props) {
+            const state = useState({ count: props.initial });
+            const increment = () => state.count++;
+            return () => <button onClick={increment}>{state.count} {props.label}</button>;
+        }
+    "#;
+
+    assert!(reported(source, "stale-setup-read").is_empty());
+}
+
+#[test]
+fn render_mutation_reports_writes_during_render() {
+    let source = r#"
+        import { useState } from "rask-ui";
+
+        function Counter() {
+            const state = useState({ count: 0, items: [] });
+            return () => {
+                state.count++;
+                state.items.push(state.count);
+                return <button onClick={() => (state.count = 0)}>{state.count}</button>;
+            };
+        }
+    "#;
+
+    assert_eq!(
+        reported(source, "render-mutation"),
+        ["state.count++", "state.items.push(state.count)"]
+    );
+}
+
+#[test]
+fn render_mutation_allows_writes_in_setup_and_handlers() {
+    let source = r#"
+        import { assignState, useState } from "rask-ui";
+
+        function Counter() {
+            const state = useState({ count: 0 });
+            state.count = 1;
+            const reset = () => assignState(state, { count: 0 });
+            return () => <button onClick={() => state.count++}>{state.count}</button>;
+        }
+    "#;
+
+    assert!(reported(source, "render-mutation").is_empty());
+}
+
+#[test]
+fn nested_component_reports_components_declared_while_rendering() {
+    let source = r#"
+        function List() {
+            return () => {
+                function Row() {
+                    return <li />;
+                }
+                return <ul><Row /></ul>;
+            };
+        }
+
+        function Table() {
+            const Cell = () => <td />;
+            return <table><Cell /></table>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "nested-component"), ["Row", "Cell"]);
+}
+
+#[test]
+fn nested_component_allows_components_declared_in_setup() {
+    let source = r#"
+        function Row() {
+            return <li />;
+        }
+
+        function List() {
+            function Header() {
+                return <h1 />;
+            }
+            return () => <ul><Header /><Row /></ul>;
+        }
+    "#;
+
+    assert!(reported(source, "nested-component").is_empty());
+}
+
+#[test]
+fn list_keys_reports_missing_and_index_keys_in_jsx() {
+    let source = r#"
+        function List(props) {
+            return () => (
+                <ul>
+                    {props.items.map((item) => <li>{item.name}</li>)}
+                    {props.items.map((item, index) => <li key={index}>{item.name}</li>)}
+                    {props.items.map((item) => <li key={item.id}>{item.name}</li>)}
+                </ul>
+            );
+        }
+    "#;
+
+    assert_eq!(reported(source, "missing-key"), ["<li>{item.name}</li>"]);
+    assert_eq!(reported(source, "index-key"), ["index"]);
+}
+
+#[test]
+fn list_keys_reads_the_key_argument_of_each_factory() {
+    // The output of the Inferno JSX transform, as the plugin sees it
+    let source = r#"
+        function List(props) {
+            return () => createVNode(1, "ul", null, [
+                props.items.map((item, index) => createVNode(1, "li", null, item.name, 0, null, index)),
+                props.items.map((item, i) => createComponentVNode(2, Row, { item }, i)),
+                props.items.map((item, index) => createFragment([item.name], 0, "item-" + index)),
+                props.items.map((item) => createVNode(1, "li", null, item.name, 0, null, null)),
+                props.items.map((item, index) => createVNode(1, "li", null, index, 0, null, item.id)),
+            ], 0);
+        }
+    "#;
+
+    assert_eq!(reported(source, "index-key"), ["index", "i", "\"item-\" + index"]);
+    assert_eq!(
+        reported(source, "missing-key"),
+        ["createVNode(1, \"li\", null, item.name, 0, null, null)"]
+    );
+}
+
+#[test]
+fn hook_placement_reports_hooks_called_while_rendering() {
+    let source = r#"
+        import { useState, useEffect } from "rask-ui";
+
+        function Counter() {
+            const state = useState({ count: 0 });
+            return () => {
+                const local = useState({ open: false });
+                return <button onClick={() => useEffect(() => {})}>{state.count}</button>;
+            };
+        }
+
+        function Label() {
+            const state = useState({ text: "" });
+            return <span>{state.text}</span>;
+        }
+    "#;
+
+    assert_eq!(
+        reported(source, "hook-in-render"),
+        ["useState({ open: false })", "useState({ text: \"\" })"]
+    );
+}
+
+#[test]
+fn hook_placement_allows_hooks_in_setup() {
+    let source = r#"
+        import { useState, useEffect } from "rask-ui";
+
+        function Counter() {
+            const state = useState({ count: 0 });
+            useEffect(() => console.log(state.count));
+            return () => <p>{state.count}</p>;
+        }
+    "#;
+
+    assert!(reported(source, "hook-in-render").is_empty());
+}
+
+#[test]
+fn this_usage_reports_this_in_components() {
+    let source = r#"
+        function Title(props) {
+            const onClick = () => this.select();
+            return () => <h1 onClick={onClick}>{this.props.title}</h1>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "this-in-component"), ["this", "this.props"]);
+}
+
+#[test]
+fn this_usage_allows_this_in_functions_and_classes() {
+    let source = r#"
+        function Title(props) {
+            const store = {
+                title: "",
+                select: function () {
+                    return this.title;
+                },
+            };
+            class Selection {
+                select() {
+                    return this;
+                }
+            }
+            return () => <h1>{props.title}</h1>;
+        }
+    "#;
+
+    assert!(reported(source, "this-in-component").is_empty());
+}
+
+#[test]
+fn missing_import_reports_each_missing_function_once() {
+    let source = r#"
+        import { useEffect } from "rask-ui";
+
+        function Counter() {
+            const state = useState({ count: 0 });
+            const other = useState({ count: 1 });
+            useEffect(() => {});
+            return () => <p>{state.count}</p>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "missing-import"), ["useState({ count: 0 })"]);
+}
+
+#[test]
+fn missing_import_allows_imported_and_declared_functions() {
+    let source = r#"
+        import { useState as useRaskState } from "rask-ui";
+        import { render } from "./render";
+
+        function useState(value) {
+            return useRaskState(value);
+        }
+
+        function Counter() {
+            const state = useState({ count: 0 });
+            return () => <p>{state.count}</p>;
+        }
+
+        render(<Counter />, document.body);
+    "#;
+
+    assert!(reported(source, "missing-import").is_empty());
+}
+
+#[test]
+fn hook_after_await_reports_hooks_evaluated_after_the_first_await() {
+    let source = r#"
+        import { useAsync, useState } from "rask-ui";
+
+        async function Profile(props) {
+            const user = useState(await load(props.id));
+            await ready(); const posts = useAsync(() => load(props.id));
+            return () => <p>{user.name}</p>;
+        }
+    "#;
+
+    assert_eq!(
+        reported(source, "hook-after-await"),
+        ["useState(await load(props.id))", "useAsync(() => load(props.id))"]
+    );
+}
+
+#[test]
+fn hook_after_await_allows_hooks_before_the_first_await() {
+    let source = r#"
+        import { useState } from "rask-ui";
+
+        async function Profile(props) {
+            const state = useState({ count: 0 });
+            const user = await load(props.id);
+            return () => <p>{user.name} {state.count}</p>;
+        }
+    "#;
+
+    assert!(reported(source, "hook-after-await").is_empty());
+}
+
+#[test]
+fn routes_report_routes_matching_the_same_paths() {
+    let source = r#"
+        import { useRouter } from "rask-ui";
+
+        function App() {
+            const router = useRouter({ user: "/users/:id", profile: "/users/:userId" });
+            return () => <p>{router.route?.name}</p>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "duplicate-route"), ["\"/users/:userId\""]);
+}
+
+#[test]
+fn routes_report_overlaps_where_neither_route_is_more_specific() {
+    let source = r#"
+        import { useRouter } from "rask-ui";
+
+        function App() {
+            const router = useRouter({ user: "/users/:id", newUser: "/users/new", section: "/:section/new" });
+            return () => <p>{router.route?.name}</p>;
+        }
+    "#;
+
+    assert_eq!(reported(source, "conflicting-route"), ["\"/:section/new\""]);
+    assert!(reported(source, "duplicate-route").is_empty());
+}
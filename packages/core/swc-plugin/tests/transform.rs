@@ -0,0 +1,461 @@
+#![cfg(feature = "native")]
+
+use serde_json::{json, Value};
+use swc_plugin_rask_component::{
+    explain::{Decision, STATEFUL_REASON, STATELESS_REASON, UNSUPPORTED_EXPORT_REASON},
+    transform_source, Config, Output,
+};
+
+/// Compile Inferno's JSX output the way the plugin does, with the given plugin options
+fn compile(source: &str, options: Value) -> Output {
+    let config: Config = serde_json::from_value(options).unwrap();
+    match transform_source(source, "Component.tsx", &config) {
+        Ok(output) => output,
+        Err(diagnostics) => panic!("{}", diagnostics),
+    }
+}
+
+fn transform(source: &str, options: Value) -> String {
+    compile(source, options).code
+}
+
+#[test]
+fn ssr_target_renders_elements_to_strings() {
+    let code = transform(
+        r#"
+        import { createVNode } from "inferno";
+
+        export function Title(props) {
+            return () => createVNode(1, "h1", null, props.text, 0);
+        }
+        "#,
+        json!({ "target": "ssr" }),
+    );
+
+    assert!(code.contains("from \"rask-ui/ssr\""));
+    assert!(code.contains("export function Title(props)"));
+    assert!(code.contains("ssrHtml(\"<h1>\" + ssrChild(props.text) + \"</h1>\")"));
+    assert!(!code.contains("createVNode"));
+}
+
+#[test]
+fn ssr_target_compiles_components_declared_in_setup() {
+    let code = transform(
+        r#"
+        import { createVNode, createComponentVNode } from "inferno";
+
+        const List = (props) => {
+            const Row = (row) => createVNode(1, "li", null, row.text, 0);
+            return () => createVNode(1, "ul", null, props.items.map((text) => createComponentVNode(2, Row, { text })), 0);
+        };
+        "#,
+        json!({ "target": "ssr" }),
+    );
+
+    assert!(code.contains("function List(props)"));
+    assert!(code.contains("function Row(row)"));
+    assert!(code.contains("\"<li>\" + ssrChild(row.text) + \"</li>\""));
+    assert!(code.contains("ssrComponent(Row, {"));
+    assert!(!code.contains("RaskStatefulComponent"));
+}
+
+#[test]
+fn ssr_target_renders_inner_html_unescaped_in_place_of_children() {
+    let source = r#"
+        import { createVNode } from "inferno";
+
+        export function Post(props) {
+            const content = { __html: props.html };
+            return createVNode(1, "article", null, "ignored", 16, { id: "post", dangerouslySetInnerHTML: content });
+        }
+    "#;
+
+    let code = transform(source, json!({ "target": "ssr" }));
+    assert!(code.contains("ssrHtml(\"<article id=\\\"post\\\">\" + ssrInnerHtml(content) + \"</article>\")"));
+    assert!(!code.contains("ignored"));
+
+    let code = transform(source, json!({ "target": "ssr", "hydrate": true }));
+    assert!(code.contains("\"<article id=\\\"post\\\"><!--[-->\" + ssrInnerHtml(content) + \"<!--]--></article>\""));
+}
+
+const COUNTER: &str = r#"
+    import { createVNode } from "inferno";
+    import { useState } from "rask-ui";
+
+    export function Counter() {
+        const state = useState({ count: 0 });
+        return () => createVNode(1, "p", null, ["Count: ", state.count], 0);
+    }
+"#;
+
+#[test]
+fn hydrate_brackets_dynamic_markup_for_the_ssr_target() {
+    let code = transform(COUNTER, json!({ "target": "ssr", "hydrate": true }));
+
+    assert!(code.contains("\"<p>Count: <!--[-->\" + ssrChild(state.count) + \"<!--]--></p>\""));
+    // The same key the client registers the structure with, so components of the same name in other files don't clash
+    assert!(code.contains("Counter.hydrationKey = \"8eee3be9:Counter\";"));
+}
+
+#[test]
+fn hydrate_registers_component_structures_for_the_client() {
+    let code = transform(COUNTER, json!({ "hydrate": true }));
+
+    assert!(code.contains("import { registerHydration } from \"rask-ui\""));
+    assert!(code.contains("import.meta.env.DEV && registerHydration(\"8eee3be9:Counter\", "));
+    assert!(code.contains("Count: "));
+    assert!(code.contains("class Counter extends RaskStatefulComponent"));
+}
+
+#[test]
+fn hydrate_is_off_by_default() {
+    assert!(!transform(COUNTER, json!({})).contains("registerHydration"));
+    let code = transform(COUNTER, json!({ "target": "ssr" }));
+    assert!(!code.contains("<!--[-->"));
+    assert!(!code.contains("hydrationKey"));
+}
+
+#[test]
+fn hook_ids_are_passed_to_hooks_imported_from_rask() {
+    let source = r#"
+        import { createVNode } from "inferno";
+        import { useState as useStore, useDerived } from "rask-ui";
+
+        export function Counter() {
+            const state = useStore({ count: 0 });
+            const double = useDerived(() => state.count * 2);
+            return () => createVNode(1, "p", null, double.value, 0);
+        }
+    "#;
+    let code = transform(source, json!({ "hookIds": true }));
+
+    assert!(code.contains("}, \"8eee3be9:Counter:0\")"));
+    assert!(code.contains("useDerived(()=>state.count * 2, \"8eee3be9:Counter:1\")"));
+}
+
+#[test]
+fn hook_ids_skip_functions_that_are_not_imported_from_rask() {
+    let source = r#"
+        import { createVNode } from "inferno";
+        import { useState } from "./store";
+
+        function useDerived(value) {
+            return { value };
+        }
+
+        export function Counter() {
+            const state = useState({ count: 0 });
+            const double = useDerived(state.count * 2);
+            return () => createVNode(1, "p", null, double.value, 0);
+        }
+    "#;
+    let code = transform(source, json!({ "hookIds": true }));
+
+    assert!(!code.contains("Counter:0"));
+}
+
+const PAGE: &str = r#"
+    import { createComponentVNode, createVNode } from "inferno";
+    import { Counter } from "./Counter";
+
+    export function Page() {
+        return createVNode(1, "main", null, createComponentVNode(2, Counter, { "client:visible": true, start: 1 }), 2);
+    }
+"#;
+
+#[test]
+fn islands_load_their_components_lazily_on_the_client() {
+    let code = transform(PAGE, json!({}));
+
+    assert!(!code.contains("from \"./Counter\""));
+    assert!(!code.contains("client:visible"));
+    assert!(code.contains("const Counter = lazy(()=>import(\"./Counter\"), \"Counter\")"));
+    assert!(code.contains("export const __raskIslands = {"));
+    assert!(code.contains("\"Counter\": ()=>import(\"./Counter\").then("));
+}
+
+#[test]
+fn islands_keep_the_import_of_components_that_are_also_rendered_statically() {
+    let source = r#"
+        import { createComponentVNode, createVNode } from "inferno";
+        import { Counter } from "./Counter";
+
+        export function Page() {
+            return createVNode(1, "main", null, [
+                createComponentVNode(2, Counter, { "client:visible": true, start: 1 }),
+                createComponentVNode(2, Counter, { start: 2 }),
+            ], 4);
+        }
+    "#;
+    let code = transform(source, json!({}));
+
+    assert!(code.contains("import { Counter } from \"./Counter\""));
+    assert!(!code.contains("lazy("));
+}
+
+#[test]
+fn islands_render_boundaries_for_the_ssr_target() {
+    let code = transform(PAGE, json!({ "target": "ssr" }));
+
+    assert!(code.contains("import { Counter } from \"./Counter\""));
+    assert!(code.contains("ssrIsland(Counter, {"));
+    assert!(code.contains("\"visible\", \"Counter\")"));
+    assert!(!code.contains("client:visible"));
+}
+
+#[test]
+fn server_actions_replace_a_use_server_module_with_stubs() {
+    let source = r#"
+        "use server";
+        import { db } from "./db";
+
+        export async function addTodo(text) {
+            return db.insert(text);
+        }
+    "#;
+    let code = transform(source, json!({ "serverActions": true }));
+
+    assert!(code.contains("import { callServerAction } from \"rask-ui\""));
+    assert!(code.contains("export function addTodo(...args) {"));
+    assert!(code.contains(":addTodo\", args)"));
+    assert!(!code.contains("./db"));
+    assert!(!code.contains("use server"));
+}
+
+#[test]
+fn server_actions_wrapped_in_parens_are_declared_at_module_level() {
+    let source = r#"
+        export const removeTodo = (async (id) => {
+            "use server";
+            return id;
+        });
+    "#;
+    // A nested "use server" function is an error, which `transform` panics on
+    let code = transform(source, json!({ "serverActions": true }));
+
+    assert!(code.contains(":removeTodo\", args)"));
+    assert!(!code.contains("use server"));
+}
+
+const TODOS: &str = r#"
+    import { createVNode } from "inferno";
+    import { db } from "./db";
+    import { format } from "./format";
+    import "./styles.css";
+
+    async function addTodo(text) {
+        "use server";
+        return db.insert(format(text));
+    }
+
+    export function Todos() {
+        return createVNode(1, "button", null, format("Add"), 0, { onClick: () => addTodo("Todo") });
+    }
+"#;
+
+#[test]
+fn server_actions_remove_the_imports_only_the_action_used() {
+    let code = transform(TODOS, json!({ "serverActions": true }));
+
+    assert!(code.contains(":addTodo\", args)"));
+    assert!(!code.contains("./db"));
+    assert!(code.contains("import { format } from \"./format\""));
+    assert!(code.contains("import \"./styles.css\""));
+}
+
+#[test]
+fn server_actions_are_registered_for_the_ssr_target() {
+    let code = transform(TODOS, json!({ "serverActions": true, "target": "ssr" }));
+
+    assert!(code.contains("import { db } from \"./db\""));
+    assert!(code.contains("return db.insert(format(text));"));
+    assert!(code.contains("export const __raskActions = {"));
+}
+
+#[test]
+fn lazy_imports_load_components_in_their_own_chunk() {
+    let source = r#"
+        import { createComponentVNode } from "inferno";
+        import { Header } from "./Header";
+        /* @rask-lazy */ import { Settings } from "./Settings";
+
+        export function App() {
+            return [createComponentVNode(2, Header), createComponentVNode(2, Settings)];
+        }
+    "#;
+    let code = transform(source, json!({}));
+
+    assert!(code.contains("import { lazy } from \"rask-ui\""));
+    assert!(code.contains("const Settings = lazy(()=>import(\"./Settings\"), \"Settings\")"));
+    assert!(!code.contains("from \"./Settings\""));
+    assert!(code.contains("import { Header } from \"./Header\""));
+}
+
+const STATUS: &str = r#"
+    import { createVNode } from "inferno";
+    import { useState } from "rask-ui";
+
+    export function Status() {
+        const state = useState({ count: 0, active: false, label: "Status" });
+        return () => createVNode(1, "p", state.active ? "on" : "off", ["Count: ", state.count], 4, {
+            title: state.label,
+            onClick: () => state.count++,
+        });
+    }
+"#;
+
+#[test]
+fn bindings_compile_reactive_text_and_attributes() {
+    let code = transform(STATUS, json!({ "bindings": true }));
+
+    assert!(code.contains("bindText(()=>state.count)"));
+    assert!(code.contains("bindAttrs({"));
+    assert!(code.contains("\"className\": ()=>state.active ? \"on\" : \"off\""));
+    assert!(code.contains("\"title\": ()=>state.label"));
+    assert!(code.contains("onClick: ()=>state.count++"));
+}
+
+#[test]
+fn bindings_are_off_by_default() {
+    let code = transform(STATUS, json!({}));
+
+    assert!(!code.contains("bindText"));
+    assert!(!code.contains("bindAttrs"));
+}
+
+#[test]
+fn lists_compile_maps_rendering_elements_to_map_list() {
+    let source = r#"
+        import { createVNode } from "inferno";
+        import { useState } from "rask-ui";
+
+        export function Todos() {
+            const state = useState({ todos: [] });
+            return () => createVNode(1, "ul", null, state.todos.map((todo) => createVNode(1, "li", null, todo.text, 0, null, todo.id)), 0);
+        }
+    "#;
+    let code = transform(source, json!({ "lists": true }));
+
+    assert!(code.contains(
+        "mapList(state.todos, (todo)=>createVNode(1, \"li\", null, todo.text, 0, null, todo.id), (todo)=>todo.id)"
+    ));
+    assert!(!code.contains(".map("));
+}
+
+#[test]
+fn lists_keep_maps_whose_key_depends_on_the_render_function() {
+    let source = r#"
+        import { createVNode } from "inferno";
+        import { useState } from "rask-ui";
+
+        export function Todos() {
+            const state = useState({ todos: [] });
+            return () => createVNode(1, "ul", null, state.todos.map((todo) => {
+                const id = todo.id;
+                return createVNode(1, "li", null, todo.text, 0, null, id);
+            }), 0);
+        }
+    "#;
+    let code = transform(source, json!({ "lists": true }));
+
+    assert!(code.contains("state.todos.map((todo)=>{"));
+    assert!(!code.contains("mapList"));
+}
+
+const COMPONENTS: &str = r#"
+    import { createVNode } from "inferno";
+    import { useState } from "rask-ui";
+
+    export function Counter(props) {
+        const state = useState({ count: props.start });
+        return () => createVNode(1, "p", null, [props.label, state.count], 0);
+    }
+
+    export default function Title(props) {
+        return createVNode(1, "h1", null, props.text, 0);
+    }
+
+    export const Badge = (props) => createVNode(1, "span", null, props.text, 0);
+"#;
+
+/// Parse the manifest comment the transform adds to the module
+fn manifest_comment(code: &str) -> Value {
+    let start = code.find("/* @rask-manifest ").expect("manifest comment") + "/* @rask-manifest ".len();
+    let end = start + code[start..].find(" */").unwrap();
+    serde_json::from_str(&code[start..end]).unwrap()
+}
+
+#[test]
+fn manifest_describes_the_compiled_components() {
+    let mut manifest = manifest_comment(&transform(COMPONENTS, json!({ "manifest": true })));
+
+    assert_eq!(manifest["components"][0]["location"]["start"]["line"], 5);
+    assert_eq!(manifest["components"][1]["location"]["start"]["line"], 10);
+    for component in manifest["components"].as_array_mut().unwrap() {
+        component.as_object_mut().unwrap().remove("location");
+    }
+    assert_eq!(
+        manifest,
+        json!({
+            "version": 1,
+            "file": "Component.tsx",
+            "components": [
+                {
+                    "name": "Counter",
+                    "exportName": "Counter",
+                    "kind": "stateful",
+                    "hooks": ["useState"],
+                    "props": ["label", "start"],
+                    "contextsUsed": [],
+                    "contextsInjected": [],
+                },
+                {
+                    "name": "Title",
+                    "exportName": "default",
+                    "kind": "stateless",
+                    "hooks": [],
+                    "props": ["text"],
+                    "contextsUsed": [],
+                    "contextsInjected": [],
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn manifest_is_off_by_default() {
+    assert!(!transform(COMPONENTS, json!({})).contains("@rask-manifest"));
+}
+
+#[test]
+fn explain_reports_why_each_candidate_was_or_was_not_compiled() {
+    let output = compile(COMPONENTS, json!({ "explain": true, "explainComments": true }));
+    let explanations: Vec<_> = output
+        .explanations
+        .iter()
+        .map(|explanation| {
+            (
+                explanation.name.as_str(),
+                explanation.decision,
+                explanation.reason.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        explanations,
+        [
+            ("Counter", Decision::Stateful, STATEFUL_REASON),
+            ("Title", Decision::Stateless, STATELESS_REASON),
+            ("Badge", Decision::Skipped, UNSUPPORTED_EXPORT_REASON),
+        ]
+    );
+    assert_eq!(output.explanations[2].location.as_ref().unwrap().start.line, 14);
+    assert!(output.code.contains("/* rask: skipped */"));
+}
+
+#[test]
+fn explain_comments_are_off_by_default() {
+    assert!(!transform(COMPONENTS, json!({ "explain": true })).contains("/* rask: "));
+}